@@ -0,0 +1,330 @@
+//! Submodule defining the report of a referential integrity audit of the
+//! foreign keys of a database.
+
+use std::fmt::Display;
+
+use sql_traits::traits::DatabaseLike;
+
+use crate::{edge_class::EdgeClass, node::Node, primary_key::PrimaryKey};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The reason why a foreign key does not define an edge class.
+pub enum IgnoredForeignKeyReason {
+    /// The host table has no primary key, hence its rows are not nodes.
+    HostWithoutPrimaryKey,
+    /// The referenced table has no primary key, hence its rows are not
+    /// nodes.
+    ReferencedWithoutPrimaryKey,
+}
+
+impl Display for IgnoredForeignKeyReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IgnoredForeignKeyReason::HostWithoutPrimaryKey => {
+                write!(f, "the host table has no primary key")
+            }
+            IgnoredForeignKeyReason::ReferencedWithoutPrimaryKey => {
+                write!(f, "the referenced table has no primary key")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A row whose foreign key values do not match any row of the referenced
+/// table.
+pub struct DanglingForeignKey<'db, DB: DatabaseLike> {
+    /// The foreign key.
+    edge_class: EdgeClass<'db, DB>,
+    /// The node of the referencing row.
+    row: Node<'db, DB>,
+    /// The foreign key values of the referencing row.
+    values: PrimaryKey,
+}
+
+impl<'db, DB: DatabaseLike> DanglingForeignKey<'db, DB> {
+    /// Returns the foreign key.
+    #[must_use]
+    pub fn edge_class(&self) -> &EdgeClass<'db, DB> {
+        &self.edge_class
+    }
+
+    /// Returns the node of the referencing row.
+    #[must_use]
+    pub fn row(&self) -> &Node<'db, DB> {
+        &self.row
+    }
+
+    /// Returns the foreign key values of the referencing row.
+    #[must_use]
+    pub fn values(&self) -> &PrimaryKey {
+        &self.values
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A row of which some, but not all, of the columns of a composite foreign
+/// key are null, so that the foreign key is not checked by the database
+/// under the default `MATCH SIMPLE` semantics.
+pub struct PartialForeignKey<'db, DB: DatabaseLike> {
+    /// The foreign key.
+    edge_class: EdgeClass<'db, DB>,
+    /// The node of the referencing row.
+    row: Node<'db, DB>,
+}
+
+impl<'db, DB: DatabaseLike> PartialForeignKey<'db, DB> {
+    /// Returns the foreign key.
+    #[must_use]
+    pub fn edge_class(&self) -> &EdgeClass<'db, DB> {
+        &self.edge_class
+    }
+
+    /// Returns the node of the referencing row.
+    #[must_use]
+    pub fn row(&self) -> &Node<'db, DB> {
+        &self.row
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A foreign key which does not define an edge class.
+pub struct IgnoredForeignKey<'db, DB: DatabaseLike> {
+    /// The foreign key.
+    edge_class: EdgeClass<'db, DB>,
+    /// The reason why the foreign key is ignored.
+    reason: IgnoredForeignKeyReason,
+}
+
+impl<'db, DB: DatabaseLike> IgnoredForeignKey<'db, DB> {
+    /// Returns the foreign key.
+    #[must_use]
+    pub fn edge_class(&self) -> &EdgeClass<'db, DB> {
+        &self.edge_class
+    }
+
+    /// Returns the reason why the foreign key is ignored.
+    #[must_use]
+    pub fn reason(&self) -> IgnoredForeignKeyReason {
+        self.reason
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The report of a referential integrity audit, listing the problems found
+/// in the foreign keys of a database without exporting it.
+///
+/// # Implementative details
+///
+/// The rows of tables without a primary key cannot be identified, hence the
+/// foreign keys they host are only reported as ignored.
+pub struct IntegrityAudit<'db, DB: DatabaseLike> {
+    /// The rows whose foreign key values do not match any referenced row.
+    dangling_foreign_keys: Vec<DanglingForeignKey<'db, DB>>,
+    /// The rows with partially null composite foreign keys.
+    partial_foreign_keys: Vec<PartialForeignKey<'db, DB>>,
+    /// The foreign keys which do not define an edge class.
+    ignored_foreign_keys: Vec<IgnoredForeignKey<'db, DB>>,
+}
+
+impl<DB: DatabaseLike> Default for IntegrityAudit<'_, DB> {
+    fn default() -> Self {
+        Self {
+            dangling_foreign_keys: Vec::new(),
+            partial_foreign_keys: Vec::new(),
+            ignored_foreign_keys: Vec::new(),
+        }
+    }
+}
+
+impl<'db, DB: DatabaseLike> IntegrityAudit<'db, DB> {
+    /// Records a row whose foreign key values do not match any referenced
+    /// row.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_class` - The foreign key.
+    /// * `row` - The node of the referencing row.
+    /// * `values` - The foreign key values of the referencing row.
+    pub(crate) fn add_dangling_foreign_key(
+        &mut self,
+        edge_class: EdgeClass<'db, DB>,
+        row: Node<'db, DB>,
+        values: PrimaryKey,
+    ) {
+        self.dangling_foreign_keys.push(DanglingForeignKey { edge_class, row, values });
+    }
+
+    /// Records a row with a partially null composite foreign key.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_class` - The foreign key.
+    /// * `row` - The node of the referencing row.
+    pub(crate) fn add_partial_foreign_key(
+        &mut self,
+        edge_class: EdgeClass<'db, DB>,
+        row: Node<'db, DB>,
+    ) {
+        self.partial_foreign_keys.push(PartialForeignKey { edge_class, row });
+    }
+
+    /// Records a foreign key which does not define an edge class.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_class` - The foreign key.
+    /// * `reason` - The reason why the foreign key is ignored.
+    pub(crate) fn add_ignored_foreign_key(
+        &mut self,
+        edge_class: EdgeClass<'db, DB>,
+        reason: IgnoredForeignKeyReason,
+    ) {
+        self.ignored_foreign_keys.push(IgnoredForeignKey { edge_class, reason });
+    }
+
+    /// Returns the rows whose foreign key values do not match any referenced
+    /// row.
+    #[must_use]
+    pub fn dangling_foreign_keys(&self) -> &[DanglingForeignKey<'db, DB>] {
+        &self.dangling_foreign_keys
+    }
+
+    /// Returns the rows with partially null composite foreign keys.
+    #[must_use]
+    pub fn partial_foreign_keys(&self) -> &[PartialForeignKey<'db, DB>] {
+        &self.partial_foreign_keys
+    }
+
+    /// Returns the foreign keys which do not define an edge class.
+    #[must_use]
+    pub fn ignored_foreign_keys(&self) -> &[IgnoredForeignKey<'db, DB>] {
+        &self.ignored_foreign_keys
+    }
+
+    /// Returns whether no dangling or partially null foreign key was found.
+    ///
+    /// # Implementative details
+    ///
+    /// Ignored foreign keys are a property of the schema rather than a
+    /// violation of referential integrity, hence they are not considered.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.dangling_foreign_keys.is_empty() && self.partial_foreign_keys.is_empty()
+    }
+}
+
+impl<DB: DatabaseLike> Display for IntegrityAudit<'_, DB> {
+    /// Displays one line per problem found.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for dangling in &self.dangling_foreign_keys {
+            writeln!(
+                f,
+                "dangling {}: {} references missing ({})",
+                dangling.edge_class, dangling.row, dangling.values
+            )?;
+        }
+        for partial in &self.partial_foreign_keys {
+            writeln!(
+                f,
+                "partial {}: {} has null and non-null values",
+                partial.edge_class, partial.row
+            )?;
+        }
+        for ignored in &self.ignored_foreign_keys {
+            writeln!(f, "ignored {}: {}", ignored.edge_class, ignored.reason)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sql_traits::{
+        prelude::ParserDB,
+        traits::{ForeignKeyLike, TableLike},
+    };
+
+    use super::*;
+
+    /// The schema of the audited database, whose `logs` table has no
+    /// primary key.
+    const SCHEMA: &str = "CREATE TABLE users (id INT PRIMARY KEY);
+        CREATE TABLE posts (id INT PRIMARY KEY, author_id INT REFERENCES users (id));
+        CREATE TABLE logs (user_id INT REFERENCES users (id));";
+
+    /// Returns the edge class of the foreign key hosted by the provided
+    /// table.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The database.
+    /// * `table_name` - The name of the host table.
+    fn edge_class<'db>(db: &'db ParserDB, table_name: &str) -> EdgeClass<'db, ParserDB> {
+        let fk = db
+            .tables()
+            .find(|table| table.table_name() == table_name)
+            .and_then(|table| table.foreign_keys(db).next())
+            .unwrap();
+        EdgeClass::new(
+            fk.host_table(db),
+            fk.host_columns(db).collect(),
+            fk.referenced_table(db),
+            fk.referenced_columns(db).collect(),
+            fk.foreign_key_name(),
+        )
+    }
+
+    #[test]
+    fn test_ignored_foreign_key_reason_display() {
+        assert_eq!(
+            IgnoredForeignKeyReason::HostWithoutPrimaryKey.to_string(),
+            "the host table has no primary key"
+        );
+        assert_eq!(
+            IgnoredForeignKeyReason::ReferencedWithoutPrimaryKey.to_string(),
+            "the referenced table has no primary key"
+        );
+    }
+
+    #[test]
+    fn test_display_and_is_clean() {
+        let db = ParserDB::try_from(SCHEMA).unwrap();
+        let mut audit = IntegrityAudit::<ParserDB>::default();
+        assert!(audit.is_clean());
+        assert_eq!(audit.to_string(), "");
+
+        // Ignored foreign keys are not violations of referential integrity.
+        audit.add_ignored_foreign_key(
+            edge_class(&db, "logs"),
+            IgnoredForeignKeyReason::HostWithoutPrimaryKey,
+        );
+        assert!(audit.is_clean());
+        assert_eq!(
+            audit.to_string(),
+            "ignored logs(user_id) -> users(id): the host table has no primary key\n"
+        );
+
+        let author = edge_class(&db, "posts");
+        let posts = author.host_table();
+        audit.add_dangling_foreign_key(
+            author.clone(),
+            Node::new(posts, PrimaryKey::I32(1)),
+            PrimaryKey::I32(7),
+        );
+        assert!(!audit.is_clean());
+        audit.add_partial_foreign_key(author, Node::new(posts, PrimaryKey::I32(2)));
+        assert_eq!(audit.dangling_foreign_keys()[0].values(), &PrimaryKey::I32(7));
+        assert_eq!(audit.partial_foreign_keys()[0].row().primary_key(), &PrimaryKey::I32(2));
+        assert_eq!(
+            audit.ignored_foreign_keys()[0].reason(),
+            IgnoredForeignKeyReason::HostWithoutPrimaryKey
+        );
+        assert_eq!(
+            audit.to_string(),
+            "dangling posts(author_id) -> users(id): posts(1) references missing (7)\n\
+             partial posts(author_id) -> users(id): posts(2) has null and non-null values\n\
+             ignored logs(user_id) -> users(id): the host table has no primary key\n"
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! Command line interface of `sql2kg`, operating on export directories.
+//!
+//! ```plain
+//! sql2kg diff <OLD> <NEW> [--verbose]
+//! ```
+//!
+//! The `diff` subcommand compares two export directories by node names and
+//! edge triples, and prints the number of added and removed nodes per node
+//! class and of added and removed edges per edge class, followed by the
+//! nodes and edges themselves when `--verbose` is provided. It exits with
+//! status 0 when the exports are equivalent, 1 when they differ and 2 on
+//! error, as `diff` does.
+
+use std::{path::Path, process::ExitCode};
+
+use sql2kg::diff::GraphDiff;
+
+/// The usage of the command line interface.
+const USAGE: &str = "usage: sql2kg diff <OLD> <NEW> [--verbose]";
+
+fn main() -> ExitCode {
+    let arguments = std::env::args().skip(1).collect::<Vec<String>>();
+    match arguments.first().map(String::as_str) {
+        Some("diff") => diff(&arguments[1..]),
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Runs the `diff` subcommand.
+///
+/// # Arguments
+///
+/// * `arguments` - The arguments following the subcommand.
+fn diff(arguments: &[String]) -> ExitCode {
+    let verbose = arguments.iter().any(|argument| argument == "--verbose");
+    let paths = arguments.iter().filter(|argument| *argument != "--verbose").collect::<Vec<_>>();
+    let [old, new] = paths.as_slice() else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+    let diff = match GraphDiff::between_directories(Path::new(old), Path::new(new)) {
+        Ok(diff) => diff,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::from(2);
+        }
+    };
+    print!("{diff}");
+    if verbose {
+        for (sign, nodes) in [('+', diff.added_nodes()), ('-', diff.removed_nodes())] {
+            for name in nodes.values().flatten() {
+                println!("{sign} {name}");
+            }
+        }
+        for (sign, edges) in [('+', diff.added_edges()), ('-', diff.removed_edges())] {
+            for (class, edges) in edges {
+                for (src, dst) in edges {
+                    println!("{sign} {src} -[{class}]-> {dst}");
+                }
+            }
+        }
+    }
+    if diff.is_empty() { ExitCode::SUCCESS } else { ExitCode::from(1) }
+}
@@ -0,0 +1,49 @@
+//! Submodule defining the token used to cooperatively cancel an export.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+#[derive(Debug, Clone, Default)]
+/// A token shared between an export and the code which may cancel it, such
+/// as a user interface or a deadline timer.
+///
+/// # Implementative details
+///
+/// The cancellation is cooperative: the export checks the token before each
+/// table, edge class and batch of rows, and returns
+/// [`Error::Cancelled`](crate::errors::Error::Cancelled) once it is
+/// cancelled. Clones of a token share the same state.
+///
+/// # Example
+///
+/// ```rust
+/// use sql2kg::cancellation::CancellationToken;
+///
+/// let token = CancellationToken::new();
+/// let handle = token.clone();
+/// assert!(!token.is_cancelled());
+/// handle.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a new `CancellationToken` instance, which is not cancelled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the exports using this token or any of its clones.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether the token has been cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
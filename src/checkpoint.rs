@@ -0,0 +1,345 @@
+//! Submodule defining the checkpoint persisted while exporting a knowledge
+//! graph, from which an interrupted export can be resumed.
+
+use std::{collections::BTreeMap, path::Path};
+
+use serde_json::{Value, json};
+use time_requirements::prelude::TimeTracker;
+
+use crate::csv::CsvWriter;
+
+/// The name of the checkpoint file written in the export directory.
+pub(crate) const CHECKPOINT_FILE_NAME: &str = "checkpoint.json";
+
+#[derive(Debug, Clone, PartialEq)]
+/// The progress of an export, recorded after each completed table and edge
+/// class.
+pub(crate) struct Checkpoint {
+    /// The fingerprint of the schema being exported.
+    schema_fingerprint: String,
+    /// The JSON representation of the export options.
+    options: Value,
+    /// The UNIX timestamp, in seconds, of the start of the first run.
+    exported_at: u64,
+    /// The JSON representation of the time tracker of the previous runs.
+    time_tracker: Value,
+    /// The number of tables whose nodes have been written.
+    completed_node_tables: usize,
+    /// The number of nodes read from the database written so far.
+    number_of_database_nodes: usize,
+    /// Whether all of the nodes, including the value nodes, have been
+    /// written.
+    nodes_completed: bool,
+    /// The number of edge classes whose edges have been written.
+    completed_edge_classes: usize,
+    /// The number of edges written so far.
+    number_of_edges: usize,
+    /// The number of dangling references encountered so far, indexed by edge
+    /// class identifier.
+    dangling_references: BTreeMap<usize, usize>,
+    /// The length of the files written so far.
+    file_lengths: BTreeMap<String, u64>,
+}
+
+impl Checkpoint {
+    /// Create a new `Checkpoint` instance for an export which has not
+    /// written anything yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema_fingerprint` - The fingerprint of the schema being exported.
+    /// * `options` - The JSON representation of the export options.
+    /// * `exported_at` - The UNIX timestamp, in seconds, of the start of the
+    ///   export.
+    pub(crate) fn new(schema_fingerprint: String, options: Value, exported_at: u64) -> Self {
+        Self {
+            schema_fingerprint,
+            options,
+            exported_at,
+            time_tracker: Value::Null,
+            completed_node_tables: 0,
+            number_of_database_nodes: 0,
+            nodes_completed: false,
+            completed_edge_classes: 0,
+            number_of_edges: 0,
+            dangling_references: BTreeMap::new(),
+            file_lengths: BTreeMap::new(),
+        }
+    }
+
+    /// Reads the checkpoint left in the provided directory, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The export directory.
+    ///
+    /// # Implementative details
+    ///
+    /// A missing or unreadable checkpoint is treated as no checkpoint, so
+    /// that the export restarts from scratch.
+    pub(crate) fn read(directory: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(directory.join(CHECKPOINT_FILE_NAME)).ok()?;
+        Self::from_json(&serde_json::from_str(&content).ok()?)
+    }
+
+    /// Parses a checkpoint from its JSON representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The JSON representation of the checkpoint.
+    fn from_json(value: &Value) -> Option<Self> {
+        let usize_field = |field: &str| {
+            value.get(field)?.as_u64().and_then(|number| usize::try_from(number).ok())
+        };
+        let pairs = |field: &str| value.get(field)?.as_object().map(|object| object.iter());
+        Some(Self {
+            schema_fingerprint: value.get("schema_fingerprint")?.as_str()?.to_owned(),
+            options: value.get("options")?.clone(),
+            exported_at: value.get("exported_at")?.as_u64()?,
+            time_tracker: value.get("time_tracker")?.clone(),
+            completed_node_tables: usize_field("completed_node_tables")?,
+            number_of_database_nodes: usize_field("number_of_database_nodes")?,
+            nodes_completed: value.get("nodes_completed")?.as_bool()?,
+            completed_edge_classes: usize_field("completed_edge_classes")?,
+            number_of_edges: usize_field("number_of_edges")?,
+            dangling_references: pairs("dangling_references")?
+                .map(|(id, count)| Some((id.parse().ok()?, usize::try_from(count.as_u64()?).ok()?)))
+                .collect::<Option<_>>()?,
+            file_lengths: pairs("file_lengths")?
+                .map(|(file_name, length)| Some((file_name.clone(), length.as_u64()?)))
+                .collect::<Option<_>>()?,
+        })
+    }
+
+    /// Returns the JSON representation of the checkpoint.
+    fn to_json(&self) -> Value {
+        json!({
+            "schema_fingerprint": self.schema_fingerprint,
+            "options": self.options,
+            "exported_at": self.exported_at,
+            "time_tracker": self.time_tracker,
+            "completed_node_tables": self.completed_node_tables,
+            "number_of_database_nodes": self.number_of_database_nodes,
+            "nodes_completed": self.nodes_completed,
+            "completed_edge_classes": self.completed_edge_classes,
+            "number_of_edges": self.number_of_edges,
+            "dangling_references": self
+                .dangling_references
+                .iter()
+                .map(|(id, count)| (id.to_string(), json!(count)))
+                .collect::<serde_json::Map<String, Value>>(),
+            "file_lengths": self.file_lengths,
+        })
+    }
+
+    /// Returns whether the checkpoint was left by an export of the provided
+    /// schema with the provided options.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema_fingerprint` - The fingerprint of the schema being exported.
+    /// * `options` - The JSON representation of the export options.
+    pub(crate) fn matches(&self, schema_fingerprint: &str, options: &Value) -> bool {
+        self.schema_fingerprint == schema_fingerprint && self.options == *options
+    }
+
+    /// Returns the UNIX timestamp, in seconds, of the start of the first run.
+    pub(crate) fn exported_at(&self) -> u64 {
+        self.exported_at
+    }
+
+    /// Returns the time tracker of the previous runs, if any.
+    pub(crate) fn time_tracker(&self) -> Option<TimeTracker> {
+        serde_json::from_value(self.time_tracker.clone()).ok()
+    }
+
+    /// Returns the number of tables whose nodes have been written.
+    pub(crate) fn completed_node_tables(&self) -> usize {
+        self.completed_node_tables
+    }
+
+    /// Returns the number of nodes read from the database written so far.
+    pub(crate) fn number_of_database_nodes(&self) -> usize {
+        self.number_of_database_nodes
+    }
+
+    /// Returns whether all of the nodes, including the value nodes, have
+    /// been written.
+    pub(crate) fn nodes_completed(&self) -> bool {
+        self.nodes_completed
+    }
+
+    /// Returns the number of edge classes whose edges have been written.
+    pub(crate) fn completed_edge_classes(&self) -> usize {
+        self.completed_edge_classes
+    }
+
+    /// Returns the number of edges written so far.
+    pub(crate) fn number_of_edges(&self) -> usize {
+        self.number_of_edges
+    }
+
+    /// Returns the number of dangling references encountered so far, indexed
+    /// by edge class identifier.
+    pub(crate) fn dangling_references(&self) -> &BTreeMap<usize, usize> {
+        &self.dangling_references
+    }
+
+    /// Returns the length of the provided file at the time of the
+    /// checkpoint, if it had been written.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The name of the file.
+    pub(crate) fn file_length(&self, file_name: &str) -> Option<u64> {
+        self.file_lengths.get(file_name).copied()
+    }
+
+    /// Records that the nodes of one more table have been written.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_of_database_nodes` - The number of nodes read from the
+    ///   database written so far.
+    pub(crate) fn complete_node_table(&mut self, number_of_database_nodes: usize) {
+        self.completed_node_tables += 1;
+        self.number_of_database_nodes = number_of_database_nodes;
+    }
+
+    /// Records that all of the nodes have been written.
+    pub(crate) fn complete_nodes(&mut self) {
+        self.nodes_completed = true;
+    }
+
+    /// Records that the edges of one more edge class have been written.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_of_edges` - The number of edges written so far.
+    /// * `dangling_references` - The number of dangling references encountered
+    ///   so far, indexed by edge class identifier.
+    pub(crate) fn complete_edge_class(
+        &mut self,
+        number_of_edges: usize,
+        dangling_references: BTreeMap<usize, usize>,
+    ) {
+        self.completed_edge_classes += 1;
+        self.number_of_edges = number_of_edges;
+        self.dangling_references = dangling_references;
+    }
+
+    /// Flushes the provided writers to disk and persists the checkpoint,
+    /// together with the provided time tracker, in the provided directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The export directory.
+    /// * `time_tracker` - The time tracker of the export so far.
+    /// * `writers` - The writers of the files being written.
+    ///
+    /// # Implementative details
+    ///
+    /// The files are synced to disk before the checkpoint records their
+    /// lengths, so that a power loss never leaves them shorter than
+    /// recorded. The checkpoint is then written to a temporary file which is
+    /// synced and renamed, so that an interruption never leaves a truncated
+    /// checkpoint.
+    pub(crate) fn save(
+        &mut self,
+        directory: &Path,
+        time_tracker: &TimeTracker,
+        writers: &mut [&mut CsvWriter],
+    ) -> Result<(), crate::errors::Error> {
+        for writer in writers.iter_mut() {
+            let length = writer.synced_length()?;
+            self.file_lengths.insert(writer.file_name().to_owned(), length);
+        }
+        self.time_tracker = serde_json::to_value(time_tracker)?;
+        let temporary_path = directory.join(format!("{CHECKPOINT_FILE_NAME}.tmp"));
+        let mut file = std::fs::File::create(&temporary_path)?;
+        std::io::Write::write_all(&mut file, &serde_json::to_vec(&self.to_json())?)?;
+        file.sync_all()?;
+        std::fs::rename(temporary_path, directory.join(CHECKPOINT_FILE_NAME))?;
+        Ok(())
+    }
+
+    /// Removes the checkpoint from the provided directory, once the export
+    /// completed.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The export directory.
+    pub(crate) fn remove(directory: &Path) -> Result<(), crate::errors::Error> {
+        match std::fs::remove_file(directory.join(CHECKPOINT_FILE_NAME)) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_json_round_trip() {
+        let mut checkpoint = Checkpoint::new(
+            "fingerprint".to_owned(),
+            json!({ "edge_orientation": "directed" }),
+            42,
+        );
+        checkpoint.complete_node_table(3);
+        checkpoint.complete_node_table(7);
+        checkpoint.complete_nodes();
+        checkpoint.complete_edge_class(11, BTreeMap::from([(0, 2), (4, 1)]));
+        checkpoint.file_lengths.insert("nodes.csv".to_owned(), 128);
+        checkpoint.time_tracker = serde_json::to_value(TimeTracker::new("Write KG CSVs")).unwrap();
+
+        let parsed = Checkpoint::from_json(&checkpoint.to_json()).unwrap();
+        assert_eq!(parsed, checkpoint);
+        assert_eq!(parsed.completed_node_tables(), 2);
+        assert_eq!(parsed.number_of_database_nodes(), 7);
+        assert!(parsed.nodes_completed());
+        assert_eq!(parsed.completed_edge_classes(), 1);
+        assert_eq!(parsed.number_of_edges(), 11);
+        assert_eq!(parsed.dangling_references().get(&4), Some(&1));
+        assert_eq!(parsed.file_length("nodes.csv"), Some(128));
+        assert_eq!(parsed.file_length("edges.csv"), None);
+        assert!(parsed.time_tracker().is_some());
+        assert!(parsed.matches("fingerprint", &json!({ "edge_orientation": "directed" })));
+        assert!(!parsed.matches("fingerprint", &json!({ "edge_orientation": "undirected" })));
+    }
+
+    #[test]
+    fn test_checkpoint_save_read_and_remove() {
+        let directory = std::env::temp_dir()
+            .join(format!("sql2kg-checkpoint-{}-save-read", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let mut writer = CsvWriter::create(&directory, "nodes.csv").unwrap();
+        std::io::Write::write_all(&mut writer, b"node,node_class_ids\n\"users(1)\",0\n").unwrap();
+        let mut checkpoint = Checkpoint::new("fingerprint".to_owned(), json!({}), 42);
+        checkpoint.complete_node_table(1);
+        checkpoint
+            .save(&directory, &TimeTracker::new("Write KG CSVs"), &mut [&mut writer])
+            .unwrap();
+
+        let read = Checkpoint::read(&directory).unwrap();
+        assert_eq!(read, checkpoint);
+        assert_eq!(read.file_length("nodes.csv"), Some(33));
+
+        Checkpoint::remove(&directory).unwrap();
+        assert!(Checkpoint::read(&directory).is_none());
+        // Removing a missing checkpoint is not an error.
+        Checkpoint::remove(&directory).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_malformed_checkpoint_is_ignored() {
+        assert!(Checkpoint::from_json(&json!({ "schema_fingerprint": "fingerprint" })).is_none());
+        let mut value = Checkpoint::new("fingerprint".to_owned(), json!({}), 42).to_json();
+        value["dangling_references"] = json!({ "not a number": 1 });
+        assert!(Checkpoint::from_json(&value).is_none());
+    }
+}
@@ -0,0 +1,365 @@
+//! Submodule providing the helpers used to write the CSV files of the
+//! knowledge graph.
+
+use std::{
+    fmt::{Display, Write},
+    io::Write as _,
+    path::Path,
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::manifest::ManifestFile;
+
+/// A wrapper displaying a value as a quoted CSV field, following RFC 4180.
+///
+/// The value is always enclosed in double quotes and any double quote it
+/// contains is escaped by doubling it, so that commas, line breaks and the
+/// `|` separator used in the `node_class_ids` column can be safely part of
+/// the value.
+///
+/// # Example
+///
+/// ```rust
+/// use sql2kg::csv::CsvField;
+///
+/// assert_eq!(CsvField("users(1)").to_string(), "\"users(1)\"");
+/// assert_eq!(CsvField("say \"hi\"").to_string(), "\"say \"\"hi\"\"\"");
+/// assert_eq!(CsvField("a,\nb").to_string(), "\"a,\nb\"");
+/// assert_eq!(CsvField("1|2").to_string(), "\"1|2\"");
+/// ```
+pub struct CsvField<T>(pub T);
+
+impl<T: Display> Display for CsvField<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_char('"')?;
+        write!(QuoteEscaper(f), "{}", self.0)?;
+        f.write_char('"')
+    }
+}
+
+/// A writer doubling the double quotes written to the wrapped formatter.
+struct QuoteEscaper<'a, 'b>(&'a mut std::fmt::Formatter<'b>);
+
+impl Write for QuoteEscaper<'_, '_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        for (i, part) in s.split('"').enumerate() {
+            if i > 0 {
+                self.0.write_str("\"\"")?;
+            }
+            self.0.write_str(part)?;
+        }
+        Ok(())
+    }
+}
+
+/// A buffered writer of a CSV file, which keeps track of the number of rows
+/// and of the SHA-256 digest of the written content.
+pub(crate) struct CsvWriter {
+    /// The name of the file being written.
+    file_name: String,
+    /// The buffered writer of the file.
+    writer: std::io::BufWriter<std::fs::File>,
+    /// The digest of the content written so far.
+    hasher: Sha256,
+    /// The number of line terminators written outside of quoted fields.
+    lines: usize,
+    /// Whether the last written byte is within a quoted field.
+    in_quotes: bool,
+    /// The number of bytes written to the file.
+    length: u64,
+}
+
+impl CsvWriter {
+    /// Creates a new CSV file with the provided name in the provided
+    /// directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory where to create the file.
+    /// * `file_name` - The name of the file.
+    pub(crate) fn create(directory: &Path, file_name: &str) -> std::io::Result<Self> {
+        let file = std::fs::File::create(directory.join(file_name))?;
+        Ok(Self {
+            file_name: file_name.to_owned(),
+            writer: std::io::BufWriter::new(file),
+            hasher: Sha256::new(),
+            lines: 0,
+            in_quotes: false,
+            length: 0,
+        })
+    }
+
+    /// Reopens a CSV file written by an interrupted export, truncating it to
+    /// the provided length so that writing continues from there, and failing
+    /// if the file is shorter than that.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory containing the file.
+    /// * `file_name` - The name of the file.
+    /// * `length` - The length of the content to keep, as recorded in the
+    ///   checkpoint.
+    pub(crate) fn resume(directory: &Path, file_name: &str, length: u64) -> std::io::Result<Self> {
+        use std::io::{Read, Seek};
+
+        let mut file =
+            std::fs::OpenOptions::new().read(true).write(true).open(directory.join(file_name))?;
+        // A file shorter than recorded was not fully written to disk, and
+        // extending it would fill it with zeros.
+        if file.metadata()?.len() < length {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!(
+                    "{file_name} is shorter than the {length} bytes recorded in the checkpoint"
+                ),
+            ));
+        }
+        file.set_len(length)?;
+        let mut content = Vec::new();
+        (&mut file).take(length).read_to_end(&mut content)?;
+        file.seek(std::io::SeekFrom::End(0))?;
+        let mut writer = Self {
+            file_name: file_name.to_owned(),
+            writer: std::io::BufWriter::new(file),
+            hasher: Sha256::new(),
+            lines: 0,
+            in_quotes: false,
+            length: 0,
+        };
+        writer.track(&content);
+        Ok(writer)
+    }
+
+    /// Returns the name of the file being written.
+    pub(crate) fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    /// Flushes the file to disk and returns the number of bytes written to
+    /// it.
+    pub(crate) fn synced_length(&mut self) -> std::io::Result<u64> {
+        self.writer.flush()?;
+        self.writer.get_ref().sync_all()?;
+        Ok(self.length)
+    }
+
+    /// Updates the number of rows, the digest and the length with the
+    /// provided written bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The bytes written to the file.
+    fn track(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            match byte {
+                b'"' => self.in_quotes = !self.in_quotes,
+                b'\n' if !self.in_quotes => self.lines += 1,
+                _ => {}
+            }
+        }
+        self.hasher.update(bytes);
+        self.length += bytes.len() as u64;
+    }
+
+    /// Flushes the file to disk and returns its manifest entry.
+    pub(crate) fn finish(mut self) -> std::io::Result<ManifestFile> {
+        self.writer.flush()?;
+        self.writer.get_ref().sync_all()?;
+        Ok(ManifestFile::new(
+            self.file_name,
+            // The header is not a row.
+            self.lines.saturating_sub(1),
+            format!("{:x}", self.hasher.finalize()),
+        ))
+    }
+}
+
+impl std::io::Write for CsvWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        self.track(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Writes a row of the nodes CSV.
+///
+/// # Arguments
+///
+/// * `writer` - The writer of the nodes CSV.
+/// * `node` - The name of the node to write.
+/// * `node_class_ids` - The identifiers of the node classes of the node.
+pub(crate) fn write_node_row<W: std::io::Write>(
+    writer: &mut W,
+    node: impl Display,
+    node_class_ids: &[usize],
+) -> std::io::Result<()> {
+    write!(writer, "{},", CsvField(node))?;
+    for (i, node_class_id) in node_class_ids.iter().enumerate() {
+        if i > 0 {
+            write!(writer, "|")?;
+        }
+        write!(writer, "{node_class_id}")?;
+    }
+    writeln!(writer)
+}
+
+/// Parses the records of the provided CSV content, following RFC 4180.
+///
+/// # Arguments
+///
+/// * `content` - The content of the CSV file.
+///
+/// # Errors
+///
+/// Returns a description of the problem if a quoted field is not properly
+/// terminated.
+pub(crate) fn parse_records(content: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut chars = content.chars().peekable();
+    let mut line: usize = 1;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() => {
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            field.push(c);
+                        }
+                        None => return Err(format!("unterminated quoted field at line {line}")),
+                    }
+                }
+                if !matches!(chars.peek(), None | Some(',' | '\r' | '\n')) {
+                    return Err(format!("unexpected character after quoted field at line {line}"));
+                }
+            }
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+                line += 1;
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use super::*;
+
+    /// Returns an empty temporary directory for the provided test.
+    fn test_directory(test_name: &str) -> std::path::PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("sql2kg-csv-{}-{test_name}", std::process::id()));
+        if directory.exists() {
+            std::fs::remove_dir_all(&directory).unwrap();
+        }
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn test_node_row_with_hostile_key_round_trip() {
+        use std::str::FromStr;
+
+        use crate::{node_name::NodeName, primary_key::PrimaryKey};
+
+        let directory = test_directory("hostile-key");
+        let node = NodeName::new(
+            Some("public".to_owned()),
+            "tags".to_owned(),
+            PrimaryKey::String("say \"hi\",\nit's a|b".to_owned()),
+        );
+
+        let mut writer = CsvWriter::create(&directory, "nodes.csv").unwrap();
+        writeln!(writer, "node,node_class_ids").unwrap();
+        write_node_row(&mut writer, &node, &[0, 2]).unwrap();
+        let file = writer.finish().unwrap();
+        assert_eq!(file.rows(), 1);
+
+        let content = std::fs::read_to_string(directory.join("nodes.csv")).unwrap();
+        let records = parse_records(&content).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].len(), 2);
+        assert_eq!(NodeName::from_str(&records[1][0]).unwrap(), node);
+        assert_eq!(records[1][1], "0|2");
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_resume_rejects_short_file() {
+        let directory = test_directory("short");
+        let mut writer = CsvWriter::create(&directory, "nodes.csv").unwrap();
+        writer.write_all(b"node,node_class_ids\n\"users(1)\",0\n").unwrap();
+        let length = writer.synced_length().unwrap();
+        drop(writer);
+
+        let error = CsvWriter::resume(&directory, "nodes.csv", length + 1).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+        // The file is left untouched.
+        assert_eq!(std::fs::metadata(directory.join("nodes.csv")).unwrap().len(), length);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_resume_truncates_and_recounts() {
+        let directory = test_directory("resume");
+        let rows = ["\"users(1)\",0\n", "\"tags('a,\nb')\",1\n", "\"users(2)\",0\n"];
+
+        // The reference file is written in a single run.
+        let mut writer = CsvWriter::create(&directory, "reference.csv").unwrap();
+        writer.write_all(b"node,node_class_ids\n").unwrap();
+        for row in rows {
+            writer.write_all(row.as_bytes()).unwrap();
+        }
+        let reference = writer.finish().unwrap();
+        assert_eq!(reference.rows(), 3);
+
+        // The interrupted run checkpoints after the first two rows, and then
+        // writes part of the third row, including a quoted line break.
+        let mut writer = CsvWriter::create(&directory, "nodes.csv").unwrap();
+        writer.write_all(b"node,node_class_ids\n").unwrap();
+        writer.write_all(rows[0].as_bytes()).unwrap();
+        writer.write_all(rows[1].as_bytes()).unwrap();
+        let length = writer.synced_length().unwrap();
+        writer.write_all(b"\"users(\n").unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+
+        let mut writer = CsvWriter::resume(&directory, "nodes.csv", length).unwrap();
+        writer.write_all(rows[2].as_bytes()).unwrap();
+        let resumed = writer.finish().unwrap();
+
+        assert_eq!(resumed.rows(), reference.rows());
+        assert_eq!(resumed.sha256(), reference.sha256());
+        assert_eq!(
+            std::fs::read(directory.join("nodes.csv")).unwrap(),
+            std::fs::read(directory.join("reference.csv")).unwrap()
+        );
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
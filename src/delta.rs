@@ -0,0 +1,120 @@
+//! Submodule defining the delta produced by an incremental export, which can
+//! be applied to the previous export.
+
+use std::path::Path;
+
+use crate::{
+    manifest::Manifest,
+    node_name::NodeName,
+    reader::{ExportedNode, parse_node_class_ids, read_file},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A delta read from the directory written by an incremental export.
+///
+/// # Implementative details
+///
+/// The directory contains:
+///
+/// * `nodes.csv`, with the same format as in a full export, listing the rows
+///   added or modified since the previous export;
+/// * `edge_classes.csv`, with the single `edge_class` column, listing the edge
+///   classes recomputed for these rows;
+/// * `edges.csv`, with the `src,dst,edge_class` columns, listing the edges of
+///   the recomputed classes originating from these rows by the names of their
+///   endpoints and of their class;
+/// * `manifest.json`, recording the start of the export the delta applies to.
+///
+/// Applying the delta replaces, for each of its nodes, the outgoing edges of
+/// the recomputed classes with the ones of the delta. Deleted rows cannot be
+/// detected through modification timestamps and are therefore left in place.
+pub struct ExportedDelta {
+    /// The added or modified nodes.
+    nodes: Vec<ExportedNode>,
+    /// The names of the recomputed edge classes.
+    edge_classes: Vec<String>,
+    /// The edges of the recomputed classes originating from the nodes, as
+    /// triples of source name, edge class name and destination name.
+    edges: Vec<(NodeName, String, NodeName)>,
+    /// The manifest of the delta.
+    manifest: Manifest,
+}
+
+impl ExportedDelta {
+    /// Reads the delta written to the provided directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The delta directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read, if it is malformed, if it
+    /// does not match the manifest, or if the directory is not a delta.
+    pub fn read(directory: &Path) -> Result<Self, crate::errors::Error> {
+        let manifest = Manifest::read(directory)?;
+        if manifest.base_exported_at().is_none() {
+            return Err(crate::errors::Error::InvalidExport(format!(
+                "{} is a full export, not a delta",
+                directory.display()
+            )));
+        }
+
+        let nodes =
+            read_file(directory, "nodes.csv", &["node", "node_class_ids"], Some(&manifest))?
+                .into_iter()
+                .enumerate()
+                .map(|(row, record)| {
+                    Ok(ExportedNode::new(
+                        record[0].parse::<NodeName>()?,
+                        // The node classes are the ones of the previous export, hence
+                        // the identifiers are checked when applying the delta.
+                        parse_node_class_ids(&record[1], usize::MAX, row)?,
+                    ))
+                })
+                .collect::<Result<Vec<ExportedNode>, crate::errors::Error>>()?;
+
+        let edge_classes =
+            read_file(directory, "edge_classes.csv", &["edge_class"], Some(&manifest))?
+                .into_iter()
+                .map(|mut record| record.remove(0))
+                .collect::<Vec<String>>();
+
+        let edges =
+            read_file(directory, "edges.csv", &["src", "dst", "edge_class"], Some(&manifest))?
+                .into_iter()
+                .map(|mut record| {
+                    let edge_class = record.remove(2);
+                    Ok((record[0].parse::<NodeName>()?, edge_class, record[1].parse::<NodeName>()?))
+                })
+                .collect::<Result<Vec<(NodeName, String, NodeName)>, crate::errors::Error>>()?;
+
+        Ok(Self { nodes, edge_classes, edges, manifest })
+    }
+
+    /// Returns the added or modified nodes.
+    #[must_use]
+    pub fn nodes(&self) -> &[ExportedNode] {
+        &self.nodes
+    }
+
+    /// Returns the names of the recomputed edge classes.
+    #[must_use]
+    pub fn edge_classes(&self) -> &[String] {
+        &self.edge_classes
+    }
+
+    /// Returns the edges of the recomputed classes originating from the
+    /// nodes, as triples of source name, edge class name and destination
+    /// name.
+    #[must_use]
+    pub fn edges(&self) -> &[(NodeName, String, NodeName)] {
+        &self.edges
+    }
+
+    /// Returns the manifest of the delta.
+    #[must_use]
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+}
@@ -0,0 +1,207 @@
+//! Submodule defining owned descriptors of nodes and edge classes, which do
+//! not borrow the database schema and can be serialized when the `serde`
+//! feature is enabled.
+
+use crate::{node_name::NodeName, primary_key::PrimaryKey};
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An owned descriptor of a node, listing its table, its primary key columns
+/// and its primary key values.
+pub struct NodeDescriptor {
+    /// The schema of the node's table, if any.
+    schema: Option<String>,
+    /// The name of the node's table.
+    table: String,
+    /// The names of the primary key columns of the node's table.
+    columns: Vec<String>,
+    /// The primary key values identifying the node.
+    key: PrimaryKey,
+}
+
+impl NodeDescriptor {
+    /// Create a new `NodeDescriptor` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the node's table, if any.
+    /// * `table` - The name of the node's table.
+    /// * `columns` - The names of the primary key columns of the node's table.
+    /// * `key` - The primary key values identifying the node.
+    #[must_use]
+    pub fn new(
+        schema: Option<String>,
+        table: String,
+        columns: Vec<String>,
+        key: PrimaryKey,
+    ) -> Self {
+        Self { schema, table, columns, key }
+    }
+
+    /// Returns the schema name of the node's table, if any.
+    #[must_use]
+    pub fn schema_name(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    /// Returns the name of the node's table.
+    #[must_use]
+    pub fn table_name(&self) -> &str {
+        &self.table
+    }
+
+    /// Returns the names of the primary key columns of the node's table.
+    #[must_use]
+    pub fn column_names(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Returns the primary key values identifying the node.
+    #[must_use]
+    pub fn primary_key(&self) -> &PrimaryKey {
+        &self.key
+    }
+
+    /// Returns the name of the node.
+    #[must_use]
+    pub fn name(&self) -> NodeName {
+        NodeName::new(self.schema.clone(), self.table.clone(), self.key.clone())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An owned descriptor of an edge class, listing its host table, its foreign
+/// key columns, the table and columns they reference and the name of the
+/// foreign key constraint.
+pub struct EdgeClassDescriptor {
+    /// The schema of the host table, if any.
+    schema: Option<String>,
+    /// The name of the host table.
+    table: String,
+    /// The names of the foreign key columns in the host table.
+    columns: Vec<String>,
+    /// The schema of the referenced table, if any.
+    referenced_schema: Option<String>,
+    /// The name of the referenced table.
+    referenced_table: String,
+    /// The names of the columns referenced by the foreign key.
+    referenced_columns: Vec<String>,
+    /// The name of the foreign key constraint, if any.
+    constraint_name: Option<String>,
+}
+
+impl EdgeClassDescriptor {
+    /// Create a new `EdgeClassDescriptor` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the host table, if any.
+    /// * `table` - The name of the host table.
+    /// * `columns` - The names of the foreign key columns in the host table.
+    /// * `referenced_schema` - The schema of the referenced table, if any.
+    /// * `referenced_table` - The name of the referenced table.
+    /// * `referenced_columns` - The names of the columns referenced by the
+    ///   foreign key.
+    /// * `constraint_name` - The name of the foreign key constraint, if any.
+    #[must_use]
+    pub fn new(
+        schema: Option<String>,
+        table: String,
+        columns: Vec<String>,
+        referenced_schema: Option<String>,
+        referenced_table: String,
+        referenced_columns: Vec<String>,
+        constraint_name: Option<String>,
+    ) -> Self {
+        Self {
+            schema,
+            table,
+            columns,
+            referenced_schema,
+            referenced_table,
+            referenced_columns,
+            constraint_name,
+        }
+    }
+
+    /// Returns the schema name of the host table, if any.
+    #[must_use]
+    pub fn schema_name(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    /// Returns the name of the host table.
+    #[must_use]
+    pub fn table_name(&self) -> &str {
+        &self.table
+    }
+
+    /// Returns the names of the foreign key columns in the host table.
+    #[must_use]
+    pub fn column_names(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Returns the schema name of the referenced table, if any.
+    #[must_use]
+    pub fn referenced_schema_name(&self) -> Option<&str> {
+        self.referenced_schema.as_deref()
+    }
+
+    /// Returns the name of the referenced table.
+    #[must_use]
+    pub fn referenced_table_name(&self) -> &str {
+        &self.referenced_table
+    }
+
+    /// Returns the names of the columns referenced by the foreign key.
+    #[must_use]
+    pub fn referenced_column_names(&self) -> &[String] {
+        &self.referenced_columns
+    }
+
+    /// Returns the name of the foreign key constraint, if any.
+    #[must_use]
+    pub fn constraint_name(&self) -> Option<&str> {
+        self.constraint_name.as_deref()
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_descriptor_serde_round_trip() {
+        let descriptor = NodeDescriptor::new(
+            Some("public".to_owned()),
+            "memberships".to_owned(),
+            vec!["user_id".to_owned(), "group_id".to_owned()],
+            PrimaryKey::Composite(vec![
+                PrimaryKey::I32(1),
+                PrimaryKey::String("admins".to_owned()),
+            ]),
+        );
+        let json = serde_json::to_string(&descriptor).unwrap();
+        assert_eq!(serde_json::from_str::<NodeDescriptor>(&json).unwrap(), descriptor);
+    }
+
+    #[test]
+    fn test_edge_class_descriptor_serde_round_trip() {
+        for (schema, constraint_name) in [(None, None), (Some("public"), Some("posts_author_fkey"))]
+        {
+            let descriptor = EdgeClassDescriptor::new(
+                schema.map(str::to_owned),
+                "posts".to_owned(),
+                vec!["author_id".to_owned()],
+                schema.map(str::to_owned),
+                "users".to_owned(),
+                vec!["id".to_owned()],
+                constraint_name.map(str::to_owned),
+            );
+            let json = serde_json::to_string(&descriptor).unwrap();
+            assert_eq!(serde_json::from_str::<EdgeClassDescriptor>(&json).unwrap(), descriptor);
+        }
+    }
+}
@@ -0,0 +1,286 @@
+//! Submodule defining the differences between two exported knowledge graphs.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    path::Path,
+};
+
+use crate::{node_name::NodeName, reader::ExportedGraph};
+
+/// An edge identified by the names of its endpoints and of its class, which
+/// does not depend on the dense identifiers of the export.
+type EdgeTriple<'a> = (&'a NodeName, &'a str, &'a NodeName);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// A struct listing the nodes and edges added and removed between two
+/// exports, grouped by node class and edge class.
+///
+/// # Implementative details
+///
+/// Nodes are compared by name and edges by the names of their source, of
+/// their edge class and of their destination, so that the renumbering of the
+/// dense identifiers between two exports does not cause spurious
+/// differences. A node whose class changed is reported as removed from its
+/// old class and added to its new one. As several rows may yield the same
+/// edge, the edges are compared as multisets, and each added or removed
+/// copy of an edge is listed.
+pub struct GraphDiff {
+    /// The added nodes, grouped by node class.
+    added_nodes: BTreeMap<String, Vec<NodeName>>,
+    /// The removed nodes, grouped by node class.
+    removed_nodes: BTreeMap<String, Vec<NodeName>>,
+    /// The added edges, as pairs of source and destination names, grouped
+    /// by edge class.
+    added_edges: BTreeMap<String, Vec<(NodeName, NodeName)>>,
+    /// The removed edges, as pairs of source and destination names, grouped
+    /// by edge class.
+    removed_edges: BTreeMap<String, Vec<(NodeName, NodeName)>>,
+}
+
+impl GraphDiff {
+    /// Computes the differences from the `old` graph to the `new` graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `old` - The graph of the previous export.
+    /// * `new` - The graph of the current export.
+    #[must_use]
+    pub fn between(old: &ExportedGraph, new: &ExportedGraph) -> Self {
+        let (old_nodes, new_nodes) = (classified_nodes(old), classified_nodes(new));
+        let (old_edges, new_edges) = (edge_triples(old), edge_triples(new));
+        let mut diff = Self::default();
+        for &(class, name) in new_nodes.difference(&old_nodes) {
+            diff.added_nodes.entry(class.to_owned()).or_default().push(name.clone());
+        }
+        for &(class, name) in old_nodes.difference(&new_nodes) {
+            diff.removed_nodes.entry(class.to_owned()).or_default().push(name.clone());
+        }
+        let add_copies = |edges: &mut BTreeMap<String, Vec<(NodeName, NodeName)>>,
+                          (src, class, dst): EdgeTriple<'_>,
+                          copies: usize| {
+            if copies > 0 {
+                edges
+                    .entry(class.to_owned())
+                    .or_default()
+                    .extend(std::iter::repeat_n((src.clone(), dst.clone()), copies));
+            }
+        };
+        for (&triple, &count) in &new_edges {
+            let old_count = old_edges.get(&triple).copied().unwrap_or(0);
+            add_copies(&mut diff.added_edges, triple, count.saturating_sub(old_count));
+        }
+        for (&triple, &count) in &old_edges {
+            let new_count = new_edges.get(&triple).copied().unwrap_or(0);
+            add_copies(&mut diff.removed_edges, triple, count.saturating_sub(new_count));
+        }
+        diff
+    }
+
+    /// Reads the exports in the provided directories and computes the
+    /// differences from the `old` export to the `new` export.
+    ///
+    /// # Arguments
+    ///
+    /// * `old` - The directory of the previous export.
+    /// * `new` - The directory of the current export.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either export cannot be read.
+    pub fn between_directories(old: &Path, new: &Path) -> Result<Self, crate::errors::Error> {
+        Ok(Self::between(&ExportedGraph::read(old)?, &ExportedGraph::read(new)?))
+    }
+
+    /// Returns the added nodes, grouped by node class.
+    #[must_use]
+    pub fn added_nodes(&self) -> &BTreeMap<String, Vec<NodeName>> {
+        &self.added_nodes
+    }
+
+    /// Returns the removed nodes, grouped by node class.
+    #[must_use]
+    pub fn removed_nodes(&self) -> &BTreeMap<String, Vec<NodeName>> {
+        &self.removed_nodes
+    }
+
+    /// Returns the added edges, as pairs of source and destination names,
+    /// grouped by edge class.
+    #[must_use]
+    pub fn added_edges(&self) -> &BTreeMap<String, Vec<(NodeName, NodeName)>> {
+        &self.added_edges
+    }
+
+    /// Returns the removed edges, as pairs of source and destination names,
+    /// grouped by edge class.
+    #[must_use]
+    pub fn removed_edges(&self) -> &BTreeMap<String, Vec<(NodeName, NodeName)>> {
+        &self.removed_edges
+    }
+
+    /// Returns whether the two graphs have the same nodes and edges.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+impl Display for GraphDiff {
+    /// Displays the number of added and removed nodes per node class and of
+    /// added and removed edges per edge class.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let summary = |f: &mut std::fmt::Formatter<'_>,
+                       added: BTreeMap<&String, usize>,
+                       removed: BTreeMap<&String, usize>|
+         -> std::fmt::Result {
+            let classes: BTreeSet<&String> = added.keys().chain(removed.keys()).copied().collect();
+            for class in classes {
+                writeln!(
+                    f,
+                    "  {class}: +{} -{}",
+                    added.get(class).copied().unwrap_or_default(),
+                    removed.get(class).copied().unwrap_or_default()
+                )?;
+            }
+            Ok(())
+        };
+        writeln!(f, "nodes:")?;
+        summary(
+            f,
+            self.added_nodes.iter().map(|(class, nodes)| (class, nodes.len())).collect(),
+            self.removed_nodes.iter().map(|(class, nodes)| (class, nodes.len())).collect(),
+        )?;
+        writeln!(f, "edges:")?;
+        summary(
+            f,
+            self.added_edges.iter().map(|(class, edges)| (class, edges.len())).collect(),
+            self.removed_edges.iter().map(|(class, edges)| (class, edges.len())).collect(),
+        )
+    }
+}
+
+/// Returns the nodes of the provided graph, as pairs of node class and name.
+///
+/// # Arguments
+///
+/// * `graph` - The exported graph.
+fn classified_nodes(graph: &ExportedGraph) -> BTreeSet<(&str, &NodeName)> {
+    graph
+        .nodes()
+        .iter()
+        .map(|node| (graph.node_classes()[node.node_class_ids()[0]].as_str(), node.name()))
+        .collect()
+}
+
+/// Returns the edges of the provided graph, as triples of names mapped to
+/// their number of occurrences.
+///
+/// # Arguments
+///
+/// * `graph` - The exported graph.
+fn edge_triples(graph: &ExportedGraph) -> BTreeMap<EdgeTriple<'_>, usize> {
+    let mut triples = BTreeMap::new();
+    for edge in graph.edges() {
+        *triples
+            .entry((
+                graph.nodes()[edge.src_id()].name(),
+                graph.edge_classes()[edge.edge_class_id()].name(),
+                graph.nodes()[edge.dst_id()].name(),
+            ))
+            .or_insert(0) += 1;
+    }
+    triples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        primary_key::PrimaryKey,
+        reader::{ExportedEdge, ExportedEdgeClass, ExportedNode},
+    };
+
+    /// The name of the edge class of the test graphs.
+    const AUTHOR: &str = "posts(author_id) -> users(id)";
+
+    /// Returns the name of the node with the provided table and key.
+    fn name(table: &str, key: i32) -> NodeName {
+        NodeName::new(None, table.to_owned(), PrimaryKey::I32(key))
+    }
+
+    /// Returns a graph with the provided nodes, as pairs of table and key,
+    /// and edges of the [`AUTHOR`] class, as pairs of node identifiers.
+    fn graph(nodes: &[(&str, i32)], edges: &[(usize, usize)]) -> ExportedGraph {
+        let node_classes = vec!["users".to_owned(), "posts".to_owned()];
+        let nodes = nodes
+            .iter()
+            .map(|&(table, key)| {
+                let node_class_id = usize::from(table == "posts");
+                ExportedNode::new(name(table, key), vec![node_class_id])
+            })
+            .collect();
+        let edge_classes = vec![ExportedEdgeClass::new(AUTHOR.to_owned(), 1, 0)];
+        let edges =
+            edges.iter().map(|&(src_id, dst_id)| ExportedEdge::new(src_id, dst_id, 0)).collect();
+        ExportedGraph::new(node_classes, nodes, edge_classes, edges)
+    }
+
+    #[test]
+    fn test_identical_graphs() {
+        let old = graph(&[("users", 1), ("posts", 1)], &[(1, 0)]);
+        // The renumbering of the identifiers is not a difference.
+        let new = graph(&[("posts", 1), ("users", 1)], &[(0, 1)]);
+        let diff = GraphDiff::between(&old, &new);
+        assert!(diff.is_empty());
+        assert_eq!(diff, GraphDiff::default());
+        assert_eq!(diff.to_string(), "nodes:\nedges:\n");
+    }
+
+    #[test]
+    fn test_added_and_removed_nodes_and_edges() {
+        let old = graph(&[("users", 1), ("users", 2), ("posts", 1)], &[(2, 0)]);
+        let new = graph(&[("users", 1), ("posts", 1), ("posts", 2)], &[(1, 0), (2, 0)]);
+        let diff = GraphDiff::between(&old, &new);
+        assert!(!diff.is_empty());
+        assert_eq!(
+            diff.added_nodes(),
+            &BTreeMap::from([("posts".to_owned(), vec![name("posts", 2)])])
+        );
+        assert_eq!(
+            diff.removed_nodes(),
+            &BTreeMap::from([("users".to_owned(), vec![name("users", 2)])])
+        );
+        assert_eq!(
+            diff.added_edges(),
+            &BTreeMap::from([(AUTHOR.to_owned(), vec![(name("posts", 2), name("users", 1))])])
+        );
+        assert!(diff.removed_edges().is_empty());
+        assert_eq!(
+            diff.to_string(),
+            format!("nodes:\n  posts: +1 -0\n  users: +0 -1\nedges:\n  {AUTHOR}: +1 -0\n")
+        );
+    }
+
+    #[test]
+    fn test_edge_multiplicity() {
+        let once = graph(&[("users", 1), ("posts", 1)], &[(1, 0)]);
+        let thrice = graph(&[("users", 1), ("posts", 1)], &[(1, 0), (1, 0), (1, 0)]);
+        let parallel_edges = vec![(name("posts", 1), name("users", 1)); 2];
+
+        let diff = GraphDiff::between(&once, &thrice);
+        assert!(!diff.is_empty());
+        assert!(diff.added_nodes().is_empty() && diff.removed_nodes().is_empty());
+        assert_eq!(
+            diff.added_edges(),
+            &BTreeMap::from([(AUTHOR.to_owned(), parallel_edges.clone())])
+        );
+        assert!(diff.removed_edges().is_empty());
+
+        let diff = GraphDiff::between(&thrice, &once);
+        assert!(diff.added_edges().is_empty());
+        assert_eq!(diff.removed_edges(), &BTreeMap::from([(AUTHOR.to_owned(), parallel_edges)]));
+    }
+}
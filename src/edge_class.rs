@@ -4,6 +4,13 @@ use std::fmt::Display;
 
 use sql_traits::traits::{ColumnLike, DatabaseLike, TableLike};
 
+use crate::{
+    descriptor::EdgeClassDescriptor,
+    edge_class_id::EdgeClassId,
+    node_name::{ClassName, Identifier},
+    traits::kg_like_db::{number_of_table_edge_classes, table_edge_classes},
+};
+
 #[derive(Debug, Clone)]
 /// A struct representing an edge class in a knowledge graph.
 pub struct EdgeClass<'db, DB: DatabaseLike> {
@@ -11,11 +18,20 @@ pub struct EdgeClass<'db, DB: DatabaseLike> {
     host_table: &'db DB::Table,
     /// The column names representing the foreign key in the host table.
     columns: Vec<&'db DB::Column>,
+    /// The table referenced by the foreign key.
+    referenced_table: &'db DB::Table,
+    /// The columns referenced by the foreign key in the referenced table.
+    referenced_columns: Vec<&'db DB::Column>,
+    /// The name of the foreign key constraint, if any.
+    constraint_name: Option<&'db str>,
 }
 
 impl<DB: DatabaseLike> PartialEq for EdgeClass<'_, DB> {
     fn eq(&self, other: &Self) -> bool {
-        self.host_table == other.host_table && self.columns == other.columns
+        self.host_table == other.host_table
+            && self.columns == other.columns
+            && self.referenced_table == other.referenced_table
+            && self.referenced_columns == other.referenced_columns
     }
 }
 
@@ -29,10 +45,11 @@ impl<DB: DatabaseLike> PartialOrd for EdgeClass<'_, DB> {
 
 impl<DB: DatabaseLike> Ord for EdgeClass<'_, DB> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match self.host_table.cmp(other.host_table) {
-            std::cmp::Ordering::Equal => self.columns.cmp(&other.columns),
-            ord => ord,
-        }
+        self.host_table
+            .cmp(other.host_table)
+            .then_with(|| self.columns.cmp(&other.columns))
+            .then_with(|| self.referenced_table.cmp(other.referenced_table))
+            .then_with(|| self.referenced_columns.cmp(&other.referenced_columns))
     }
 }
 
@@ -42,6 +59,10 @@ impl<DB: DatabaseLike> std::hash::Hash for EdgeClass<'_, DB> {
         for column in &self.columns {
             column.hash(state);
         }
+        self.referenced_table.hash(state);
+        for column in &self.referenced_columns {
+            column.hash(state);
+        }
     }
 }
 
@@ -53,23 +74,123 @@ impl<'db, DB: DatabaseLike> EdgeClass<'db, DB> {
     /// * `host_table` - The table from which the edge originates.
     /// * `columns` - The columns representing the foreign key in the host
     ///   table.
-    pub(crate) fn new(host_table: &'db DB::Table, columns: Vec<&'db DB::Column>) -> Self {
-        Self { host_table, columns }
+    /// * `referenced_table` - The table referenced by the foreign key.
+    /// * `referenced_columns` - The columns referenced by the foreign key.
+    /// * `constraint_name` - The name of the foreign key constraint, if any.
+    pub(crate) fn new(
+        host_table: &'db DB::Table,
+        columns: Vec<&'db DB::Column>,
+        referenced_table: &'db DB::Table,
+        referenced_columns: Vec<&'db DB::Column>,
+        constraint_name: Option<&'db str>,
+    ) -> Self {
+        Self { host_table, columns, referenced_table, referenced_columns, constraint_name }
+    }
+
+    /// Returns a reference to the table from which the edges originate.
+    #[must_use]
+    pub fn host_table(&self) -> &'db DB::Table {
+        self.host_table
+    }
+
+    /// Returns the columns representing the foreign key in the host table.
+    #[must_use]
+    pub fn columns(&self) -> &[&'db DB::Column] {
+        &self.columns
+    }
+
+    /// Returns a reference to the table the edges point to.
+    #[must_use]
+    pub fn referenced_table(&self) -> &'db DB::Table {
+        self.referenced_table
+    }
+
+    /// Returns the columns referenced by the foreign key in the referenced
+    /// table.
+    #[must_use]
+    pub fn referenced_columns(&self) -> &[&'db DB::Column] {
+        &self.referenced_columns
+    }
+
+    /// Returns the name of the foreign key constraint, if any.
+    #[must_use]
+    pub fn constraint_name(&self) -> Option<&'db str> {
+        self.constraint_name
+    }
+
+    /// Returns the owned identifier of the edge class, if it is one of the
+    /// edge classes of the provided database.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The database containing the edge class.
+    ///
+    /// # Implementative details
+    ///
+    /// Since the edge classes are sorted within each host table, the tables
+    /// preceding the host table are only counted, and the edge class is
+    /// binary searched among the ones of its host table.
+    #[must_use]
+    pub fn id(&self, database: &DB) -> Option<EdgeClassId> {
+        let mut offset = 0;
+        for table in database.tables() {
+            if table == self.host_table {
+                let position = table_edge_classes(database, table).binary_search(self).ok()?;
+                return Some(EdgeClassId::new(offset + position));
+            }
+            offset += number_of_table_edge_classes(database, table);
+        }
+        None
+    }
+
+    /// Returns the owned descriptor of the edge class.
+    #[must_use]
+    pub fn descriptor(&self) -> EdgeClassDescriptor {
+        EdgeClassDescriptor::new(
+            self.host_table.table_schema().map(str::to_owned),
+            self.host_table.table_name().to_owned(),
+            self.columns.iter().map(|column| column.column_name().to_owned()).collect(),
+            self.referenced_table.table_schema().map(str::to_owned),
+            self.referenced_table.table_name().to_owned(),
+            self.referenced_columns.iter().map(|column| column.column_name().to_owned()).collect(),
+            self.constraint_name.map(str::to_owned),
+        )
     }
 }
 
 impl<DB: DatabaseLike> Display for EdgeClass<'_, DB> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if let Some(schema) = self.host_table.table_schema() {
-            write!(f, "{schema}.")?;
-        }
-        write!(f, "{}(", self.host_table.table_name())?;
-        for (i, column) in self.columns.iter().enumerate() {
-            if i > 0 {
-                write!(f, ", ")?;
+        // The name, such as `posts(author_id) -> users(id)`, includes the
+        // referenced table and columns, so that the foreign keys sharing their
+        // host columns are told apart.
+        let write_columns =
+            |f: &mut std::fmt::Formatter<'_>, columns: &[&DB::Column]| -> std::fmt::Result {
+                for (i, column) in columns.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", Identifier(column.column_name()))?;
+                }
+                Ok(())
+            };
+        write!(
+            f,
+            "{}(",
+            ClassName {
+                schema: self.host_table.table_schema(),
+                table: self.host_table.table_name()
             }
-            write!(f, "{}", column.column_name())?;
-        }
+        )?;
+        write_columns(f, &self.columns)?;
+        write!(
+            f,
+            ") -> {}(",
+            ClassName {
+                schema: self.referenced_table.table_schema(),
+                table: self.referenced_table.table_name()
+            }
+        )?;
+        write_columns(f, &self.referenced_columns)?;
         write!(f, ")")
     }
 }
@@ -0,0 +1,118 @@
+//! Submodule defining an owned identifier of an edge class, independent of
+//! the lifetime of the database schema.
+
+use std::fmt::Display;
+
+use sql_traits::traits::DatabaseLike;
+
+use crate::{
+    edge_class::EdgeClass,
+    traits::kg_like_db::{number_of_table_edge_classes, table_edge_classes},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An owned identifier of an edge class, i.e. its position among the edge
+/// classes returned by
+/// [`KGLikeDB::edge_classes`](crate::traits::KGLikeDB::edge_classes), which is
+/// also its row in `edge_classes.csv`.
+///
+/// Unlike [`EdgeClass`], an `EdgeClassId` does not borrow the database schema,
+/// and can therefore be stored beyond the lifetime of the schema or sent
+/// across threads.
+pub struct EdgeClassId(usize);
+
+impl EdgeClassId {
+    /// Create a new `EdgeClassId` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The position of the edge class.
+    #[must_use]
+    pub fn new(id: usize) -> Self {
+        Self(id)
+    }
+
+    /// Returns the position of the edge class.
+    #[must_use]
+    pub fn id(self) -> usize {
+        self.0
+    }
+
+    /// Returns the borrowed edge class corresponding to the identifier, if it
+    /// exists in the provided database.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The database containing the edge class.
+    ///
+    /// # Implementative details
+    ///
+    /// The edge classes of the tables preceding the one hosting the edge
+    /// class are only counted, so that only the edge classes of its host
+    /// table are built.
+    #[must_use]
+    pub fn resolve<DB: DatabaseLike>(self, database: &DB) -> Option<EdgeClass<'_, DB>> {
+        let mut position = self.0;
+        for table in database.tables() {
+            let number_of_edge_classes = number_of_table_edge_classes(database, table);
+            if position < number_of_edge_classes {
+                return table_edge_classes(database, table).into_iter().nth(position);
+            }
+            position -= number_of_edge_classes;
+        }
+        None
+    }
+}
+
+impl From<EdgeClassId> for usize {
+    fn from(id: EdgeClassId) -> Self {
+        id.0
+    }
+}
+
+impl Display for EdgeClassId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sql_traits::prelude::ParserDB;
+
+    use super::*;
+    use crate::traits::KGLikeDB;
+
+    #[test]
+    fn test_round_trip() {
+        // The tables without a primary key host or receive no edge class, and
+        // the foreign keys of `posts` are declared out of order.
+        let db = ParserDB::try_from(
+            "CREATE TABLE users (id INT PRIMARY KEY);
+            CREATE TABLE logs (user_id INT REFERENCES users (id));
+            CREATE TABLE tags (name TEXT);
+            CREATE TABLE posts (
+                id INT PRIMARY KEY,
+                editor_id INT REFERENCES users (id),
+                author_id INT REFERENCES users (id),
+                tag TEXT REFERENCES tags (name)
+            );
+            CREATE TABLE comments (
+                id INT PRIMARY KEY,
+                post_id INT REFERENCES posts (id),
+                author_id INT REFERENCES users (id)
+            );",
+        )
+        .unwrap();
+
+        let edge_classes = db.edge_classes().collect::<Vec<EdgeClass<'_, ParserDB>>>();
+        assert_eq!(edge_classes.len(), 4);
+        for (position, edge_class) in edge_classes.iter().enumerate() {
+            let id = edge_class.id(&db).unwrap();
+            assert_eq!(id, EdgeClassId::new(position));
+            assert_eq!(id.resolve(&db).as_ref(), Some(edge_class));
+        }
+        assert!(EdgeClassId::new(edge_classes.len()).resolve(&db).is_none());
+    }
+}
@@ -1,5 +1,7 @@
 //! Errors that can occur during SQL to Knowledge Graph conversion.
 
+use crate::primary_key::PrimaryKey;
+
 #[derive(Debug, thiserror::Error)]
 /// Enum representing errors that can occur in the SQL to Knowledge Graph
 /// conversion process.
@@ -10,6 +12,37 @@ pub enum Error {
     /// An IO error occurred.
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+    /// A JSON error occurred.
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    /// A query on a table failed.
+    #[error("Query on table {table} failed: {source}")]
+    Query {
+        /// The name of the queried table.
+        table: String,
+        /// The error returned by diesel.
+        source: diesel::result::Error,
+    },
+    /// A key column has a type which cannot be converted into a primary key.
+    #[error("Unsupported key type with OID {oid} in column {column} of table {table}")]
+    UnsupportedKeyType {
+        /// The name of the table.
+        table: String,
+        /// The name of the key column.
+        column: String,
+        /// The OID of the type of the key column.
+        oid: u32,
+    },
+    /// A foreign key value references a row which does not exist.
+    #[error("Dangling reference of edge class {edge_class} from {row} to {referenced}")]
+    DanglingReference {
+        /// The name of the edge class.
+        edge_class: String,
+        /// The name of the node of the referencing row.
+        row: String,
+        /// The name of the missing referenced node.
+        referenced: String,
+    },
     /// A node from the edge list could not be found in the node list.
     #[error("Node not found: {0}")]
     NodeNotFound(String),
@@ -17,4 +50,58 @@ pub enum Error {
     /// list.
     #[error("Edge class not found: {0}")]
     EdgeClassNotFound(String),
+    /// A table referenced in the export options could not be found.
+    #[error("Table not found: {0}")]
+    TableNotFound(String),
+    /// A table referenced in the export options by its name alone matches
+    /// tables of several schemas.
+    #[error("Ambiguous table name, qualify it with its schema: {0}")]
+    AmbiguousTable(String),
+    /// A table referenced in the export options has no primary key, hence its
+    /// rows are not nodes.
+    #[error("Table without primary key: {0}")]
+    TableWithoutPrimaryKey(String),
+    /// A column referenced in the export options could not be found.
+    #[error("Column not found: {0}")]
+    ColumnNotFound(String),
+    /// A primary key could not be parsed from its textual representation.
+    #[error("Invalid primary key: {0}")]
+    InvalidPrimaryKey(String),
+    /// A node name could not be parsed.
+    #[error("Invalid node name: {0}")]
+    InvalidNodeName(String),
+    /// A manifest could not be interpreted.
+    #[error("Invalid manifest: {0}")]
+    InvalidManifest(String),
+    /// An export directory could not be read back.
+    #[error("Invalid export: {0}")]
+    InvalidExport(String),
+    /// A snapshot was requested for an export on a connection which is
+    /// already within a transaction, where it cannot be imported.
+    #[error(
+        "Cannot import a snapshot within an open transaction, export on a connection outside of \
+         any transaction or without a snapshot"
+    )]
+    SnapshotWithinTransaction,
+    /// The snapshot requested for an export could not be imported, for
+    /// instance because the transaction which exported it has ended.
+    #[error("Cannot import snapshot {snapshot}: {source}")]
+    SnapshotImport {
+        /// The identifier of the snapshot.
+        snapshot: String,
+        /// The error returned by diesel.
+        source: diesel::result::Error,
+    },
+    /// A node written by an interrupted export belongs to a table which is no
+    /// longer in the database, hence the export cannot be resumed.
+    #[error("Cannot resume the export, node ({key}) of table {table} cannot be found")]
+    ResumedNodeNotFound {
+        /// The name of the node's table, qualified with its schema if any.
+        table: String,
+        /// The primary key of the node.
+        key: PrimaryKey,
+    },
+    /// The export was cancelled through its cancellation token.
+    #[error("Export cancelled")]
+    Cancelled,
 }
@@ -0,0 +1,203 @@
+//! Submodule defining the loader configuration written next to the CSVs of
+//! an export, listing the arguments of grape's `Graph.from_csv`.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{Value, json};
+
+/// The name of the grape loader configuration file written in the export
+/// directory.
+pub const GRAPE_CONFIG_FILE_NAME: &str = "grape.json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A struct representing the arguments of grape's `Graph.from_csv` needed to
+/// load an export.
+///
+/// # Example
+///
+/// ```rust
+/// use std::path::Path;
+///
+/// use sql2kg::grape::GrapeConfig;
+///
+/// let config = GrapeConfig::new("My KG", Path::new("kg"), 10, 2, 1);
+/// let arguments = config.to_json();
+/// assert_eq!(arguments["name"], "My KG");
+/// assert_eq!(arguments["directed"], true);
+/// assert_eq!(arguments["node_path"], "kg/nodes.csv");
+/// assert_eq!(arguments["number_of_nodes"], 10);
+/// ```
+pub struct GrapeConfig {
+    /// The name of the graph.
+    name: String,
+    /// The directory containing the CSV files.
+    directory: PathBuf,
+    /// Whether the graph is directed.
+    directed: bool,
+    /// The number of nodes.
+    number_of_nodes: usize,
+    /// The number of node classes.
+    number_of_node_types: usize,
+    /// The number of edge classes.
+    number_of_edge_types: usize,
+}
+
+impl GrapeConfig {
+    /// Create a new `GrapeConfig` instance describing a directed graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the graph.
+    /// * `directory` - The directory containing the CSV files, as it should be
+    ///   referenced by the loader.
+    /// * `number_of_nodes` - The number of nodes.
+    /// * `number_of_node_types` - The number of node classes.
+    /// * `number_of_edge_types` - The number of edge classes.
+    #[must_use]
+    pub fn new(
+        name: impl Into<String>,
+        directory: &Path,
+        number_of_nodes: usize,
+        number_of_node_types: usize,
+        number_of_edge_types: usize,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            directory: directory.to_path_buf(),
+            directed: true,
+            number_of_nodes,
+            number_of_node_types,
+            number_of_edge_types,
+        }
+    }
+
+    /// Sets whether the graph is directed.
+    ///
+    /// # Arguments
+    ///
+    /// * `directed` - Whether the graph is directed.
+    #[must_use]
+    pub fn with_directed(mut self, directed: bool) -> Self {
+        self.directed = directed;
+        self
+    }
+
+    /// Returns the name of the graph.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns whether the graph is directed.
+    #[must_use]
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Returns the path of the provided file of the export, as a string.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The name of the file.
+    fn file_path(&self, file_name: &str) -> String {
+        self.directory.join(file_name).to_string_lossy().into_owned()
+    }
+
+    /// Returns the keyword arguments of `Graph.from_csv`, as a JSON object.
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "directed": self.directed,
+            "node_type_path": self.file_path("node_classes.csv"),
+            "node_types_column": "node_class",
+            "node_type_list_separator": ",",
+            "node_type_list_header": true,
+            "node_path": self.file_path("nodes.csv"),
+            "nodes_column": "node",
+            "node_list_separator": ",",
+            "node_list_header": true,
+            "node_types_separator": "|",
+            "node_list_node_types_column": "node_class_ids",
+            "node_list_numeric_node_type_ids": true,
+            "edge_type_path": self.file_path("edge_classes.csv"),
+            "edge_types_column": "edge_class",
+            "edge_type_list_separator": ",",
+            "edge_type_list_header": true,
+            "edge_path": self.file_path("edges.csv"),
+            "edge_list_separator": ",",
+            "edge_list_header": true,
+            "sources_column": "src_id",
+            "destinations_column": "dst_id",
+            "edge_list_edge_types_column": "edge_class_id",
+            "edge_list_numeric_node_ids": true,
+            "edge_list_numeric_edge_type_ids": true,
+            "number_of_nodes": self.number_of_nodes,
+            "number_of_node_types": self.number_of_node_types,
+            "number_of_edge_types": self.number_of_edge_types,
+        })
+    }
+
+    /// Writes the configuration to the `grape.json` file of the provided
+    /// directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The export directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the file fails.
+    pub fn write(&self, directory: &Path) -> Result<(), crate::errors::Error> {
+        let file = std::fs::File::create(directory.join(GRAPE_CONFIG_FILE_NAME))?;
+        let mut writer = std::io::BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, &self.to_json())?;
+        std::io::Write::flush(&mut writer)?;
+        writer.get_ref().sync_all()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let config = GrapeConfig::new("kg", Path::new("/exports/kg"), 10, 2, 3);
+        assert!(config.is_directed());
+        let arguments = config.to_json();
+        assert_eq!(arguments["name"], "kg");
+        assert_eq!(arguments["directed"], true);
+        assert_eq!(arguments["node_type_path"], "/exports/kg/node_classes.csv");
+        assert_eq!(arguments["node_path"], "/exports/kg/nodes.csv");
+        assert_eq!(arguments["edge_type_path"], "/exports/kg/edge_classes.csv");
+        assert_eq!(arguments["edge_path"], "/exports/kg/edges.csv");
+        assert_eq!(arguments["number_of_nodes"], 10);
+        assert_eq!(arguments["number_of_node_types"], 2);
+        assert_eq!(arguments["number_of_edge_types"], 3);
+
+        let config = config.with_directed(false);
+        assert!(!config.is_directed());
+        assert_eq!(config.to_json()["directed"], false);
+    }
+
+    #[test]
+    fn test_write() {
+        let directory =
+            std::env::temp_dir().join(format!("sql2kg-grape-{}-write", std::process::id()));
+        if directory.exists() {
+            std::fs::remove_dir_all(&directory).unwrap();
+        }
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let config = GrapeConfig::new("kg", &directory, 10, 2, 3).with_directed(false);
+        config.write(&directory).unwrap();
+        let written: Value = serde_json::from_str(
+            &std::fs::read_to_string(directory.join(GRAPE_CONFIG_FILE_NAME)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(written, config.to_json());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
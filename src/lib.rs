@@ -1,11 +1,60 @@
 //! SQL to Knowledge Graph conversion library.
+pub mod audit;
+pub mod cancellation;
+mod checkpoint;
+pub mod csv;
+pub mod delta;
+pub mod descriptor;
+pub mod diff;
 pub mod edge_class;
+pub mod edge_class_id;
 pub mod errors;
+pub mod grape;
+pub mod manifest;
 pub mod node;
+pub mod node_name;
+pub mod node_ref;
+pub mod options;
+mod parser;
 pub mod primary_key;
+pub mod progress;
+pub mod property_value;
+pub mod reader;
+pub mod report;
+pub mod schema_graph;
+mod sql;
+mod staging;
+pub mod statistics;
 pub mod traits;
 
 /// Prelude module re-exporting commonly used items.
 pub mod prelude {
-    pub use crate::{edge_class::EdgeClass, node::Node, primary_key::PrimaryKey, traits::KGLikeDB};
+    pub use crate::{
+        audit::{
+            DanglingForeignKey, IgnoredForeignKey, IgnoredForeignKeyReason, IntegrityAudit,
+            PartialForeignKey,
+        },
+        cancellation::CancellationToken,
+        delta::ExportedDelta,
+        descriptor::{EdgeClassDescriptor, NodeDescriptor},
+        diff::GraphDiff,
+        edge_class::EdgeClass,
+        edge_class_id::EdgeClassId,
+        grape::GrapeConfig,
+        manifest::{Manifest, ManifestFile},
+        node::Node,
+        node_name::NodeName,
+        node_ref::NodeRef,
+        options::{DanglingReferencePolicy, EdgeOrientation, ExportOptions},
+        primary_key::PrimaryKey,
+        progress::{ExportPhase, Progress, ProgressObserver},
+        property_value::PropertyValue,
+        reader::{ExportedEdge, ExportedEdgeClass, ExportedGraph, ExportedNode},
+        report::ExportReport,
+        schema_graph::{SchemaEdgeClass, SchemaNodeClass},
+        statistics::{
+            DegreeStatistics, EdgeClassStatistics, ExportStatistics, NodeClassStatistics,
+        },
+        traits::KGLikeDB,
+    };
 }
@@ -0,0 +1,542 @@
+//! Submodule defining the manifest written next to the CSVs of an export,
+//! recording what was produced.
+
+use std::path::Path;
+
+use serde_json::{Value, json};
+
+use crate::{grape::GrapeConfig, options::EdgeOrientation};
+
+/// The name of the manifest file written in the export directory.
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// The version of the manifest format.
+const FORMAT_VERSION: u64 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An entry of the manifest describing one of the exported files.
+pub struct ManifestFile {
+    /// The name of the file, relative to the export directory.
+    path: String,
+    /// The number of rows of the file, excluding the header.
+    rows: usize,
+    /// The hex-encoded SHA-256 digest of the content of the file.
+    sha256: String,
+}
+
+impl ManifestFile {
+    /// Create a new `ManifestFile` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The name of the file, relative to the export directory.
+    /// * `rows` - The number of rows of the file, excluding the header.
+    /// * `sha256` - The hex-encoded SHA-256 digest of the content of the file.
+    #[must_use]
+    pub fn new(path: String, rows: usize, sha256: String) -> Self {
+        Self { path, rows, sha256 }
+    }
+
+    /// Returns the name of the file, relative to the export directory.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the number of rows of the file, excluding the header.
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the hex-encoded SHA-256 digest of the content of the file.
+    #[must_use]
+    pub fn sha256(&self) -> &str {
+        &self.sha256
+    }
+
+    /// Returns the JSON representation of the entry.
+    fn to_json(&self) -> Value {
+        json!({ "path": self.path, "rows": self.rows, "sha256": self.sha256 })
+    }
+
+    /// Parses an entry from its JSON representation.
+    fn from_json(value: &Value) -> Result<Self, crate::errors::Error> {
+        Ok(Self {
+            path: string_field(value, "path")?.to_owned(),
+            rows: usize_field(value, "rows")?,
+            sha256: string_field(value, "sha256")?.to_owned(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A struct representing the manifest of an export, listing the produced
+/// files together with the figures needed to load the graph.
+pub struct Manifest {
+    /// The UNIX timestamp, in seconds, of the start of the export.
+    exported_at: u64,
+    /// The fingerprint of the schema of the exported database.
+    schema_fingerprint: String,
+    /// The number of nodes.
+    number_of_nodes: usize,
+    /// The number of node classes.
+    number_of_node_classes: usize,
+    /// The number of edges.
+    number_of_edges: usize,
+    /// The number of edge classes.
+    number_of_edge_classes: usize,
+    /// The exported files.
+    files: Vec<ManifestFile>,
+    /// The options used for the export.
+    options: Value,
+    /// The UNIX timestamp, in seconds, of the start of the export a delta
+    /// should be applied to, if the export is a delta.
+    base_exported_at: Option<u64>,
+}
+
+impl Manifest {
+    /// Create a new `Manifest` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `exported_at` - The UNIX timestamp, in seconds, of the start of the
+    ///   export.
+    /// * `schema_fingerprint` - The fingerprint of the exported schema.
+    /// * `files` - The exported files.
+    /// * `options` - The JSON representation of the export options.
+    ///
+    /// # Implementative details
+    ///
+    /// The number of nodes, node classes, edges and edge classes are the
+    /// number of rows of the corresponding files.
+    pub(crate) fn new(
+        exported_at: u64,
+        schema_fingerprint: String,
+        files: Vec<ManifestFile>,
+        options: Value,
+    ) -> Self {
+        let rows = |name: &str| files.iter().find(|file| file.path == name).map_or(0, |f| f.rows);
+        Self {
+            exported_at,
+            schema_fingerprint,
+            number_of_nodes: rows("nodes.csv"),
+            number_of_node_classes: rows("node_classes.csv"),
+            number_of_edges: rows("edges.csv"),
+            number_of_edge_classes: rows("edge_classes.csv"),
+            files,
+            options,
+            base_exported_at: None,
+        }
+    }
+
+    /// Marks the manifest as the one of a delta, to be applied to the
+    /// export started at the provided timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_exported_at` - The UNIX timestamp, in seconds, of the start of
+    ///   the export the delta should be applied to.
+    #[must_use]
+    pub(crate) fn with_base_exported_at(mut self, base_exported_at: u64) -> Self {
+        self.base_exported_at = Some(base_exported_at);
+        self
+    }
+
+    /// Returns the manifest of the graph obtained by applying the delta with
+    /// the provided manifest to the export with this manifest.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - The manifest of the delta.
+    /// * `number_of_nodes` - The number of nodes after applying the delta.
+    /// * `number_of_edges` - The number of edges after applying the delta.
+    ///
+    /// # Implementative details
+    ///
+    /// As the files of the resulting graph are yet to be written, the
+    /// returned manifest does not list any file.
+    pub(crate) fn after_delta(
+        &self,
+        delta: &Manifest,
+        number_of_nodes: usize,
+        number_of_edges: usize,
+    ) -> Self {
+        Self {
+            exported_at: delta.exported_at,
+            schema_fingerprint: self.schema_fingerprint.clone(),
+            number_of_nodes,
+            number_of_node_classes: self.number_of_node_classes,
+            number_of_edges,
+            number_of_edge_classes: self.number_of_edge_classes,
+            files: Vec::new(),
+            options: self.options.clone(),
+            base_exported_at: None,
+        }
+    }
+
+    /// Returns the UNIX timestamp, in seconds, of the start of the export.
+    ///
+    /// # Implementative details
+    ///
+    /// The timestamp is read from the database server, as the start of the
+    /// oldest transaction running when the export started, so that it can
+    /// be used as the cutoff of the next delta.
+    #[must_use]
+    pub fn exported_at(&self) -> u64 {
+        self.exported_at
+    }
+
+    /// Returns the UNIX timestamp, in seconds, of the start of the export
+    /// this delta should be applied to, or `None` if this is the manifest of
+    /// a full export.
+    #[must_use]
+    pub fn base_exported_at(&self) -> Option<u64> {
+        self.base_exported_at
+    }
+
+    /// Returns the fingerprint of the schema of the exported database.
+    #[must_use]
+    pub fn schema_fingerprint(&self) -> &str {
+        &self.schema_fingerprint
+    }
+
+    /// Returns the number of nodes.
+    #[must_use]
+    pub fn number_of_nodes(&self) -> usize {
+        self.number_of_nodes
+    }
+
+    /// Returns the number of node classes.
+    #[must_use]
+    pub fn number_of_node_classes(&self) -> usize {
+        self.number_of_node_classes
+    }
+
+    /// Returns the number of edges.
+    #[must_use]
+    pub fn number_of_edges(&self) -> usize {
+        self.number_of_edges
+    }
+
+    /// Returns the number of edge classes.
+    #[must_use]
+    pub fn number_of_edge_classes(&self) -> usize {
+        self.number_of_edge_classes
+    }
+
+    /// Returns the exported files.
+    #[must_use]
+    pub fn files(&self) -> &[ManifestFile] {
+        &self.files
+    }
+
+    /// Returns the exported file with the provided name, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The name of the file, relative to the export directory.
+    #[must_use]
+    pub fn file(&self, path: &str) -> Option<&ManifestFile> {
+        self.files.iter().find(|file| file.path == path)
+    }
+
+    /// Returns the JSON representation of the options used for the export.
+    #[must_use]
+    pub fn options(&self) -> &Value {
+        &self.options
+    }
+
+    /// Returns the grape loader configuration of the export.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The export directory, as it should be referenced by the
+    ///   loader.
+    ///
+    /// # Implementative details
+    ///
+    /// The directory is made absolute, so that the paths of the configuration
+    /// resolve from any working directory and not only from the one of the
+    /// export. The graph is named after the export directory, and is
+    /// undirected only if the export was flagged as such through its options.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the current working directory cannot be
+    /// determined or the directory cannot be canonicalized.
+    pub fn grape_config(&self, directory: &Path) -> Result<GrapeConfig, crate::errors::Error> {
+        let directory = crate::staging::absolute(directory)?;
+        Ok(GrapeConfig::new(
+            graph_name(&directory),
+            &directory,
+            self.number_of_nodes,
+            self.number_of_node_classes,
+            self.number_of_edge_classes,
+        )
+        .with_directed(
+            self.options.get("edge_orientation").and_then(Value::as_str)
+                != Some(EdgeOrientation::Undirected.name()),
+        ))
+    }
+
+    /// Returns the JSON representation of the manifest.
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "format_version": FORMAT_VERSION,
+            "generator": format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            "exported_at": self.exported_at,
+            "base_exported_at": self.base_exported_at,
+            "schema_fingerprint": self.schema_fingerprint,
+            "number_of_nodes": self.number_of_nodes,
+            "number_of_node_classes": self.number_of_node_classes,
+            "number_of_edges": self.number_of_edges,
+            "number_of_edge_classes": self.number_of_edge_classes,
+            "files": self.files.iter().map(ManifestFile::to_json).collect::<Vec<Value>>(),
+            "options": self.options,
+        })
+    }
+
+    /// Parses a manifest from its JSON representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The JSON representation of the manifest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field is missing or has an unexpected type.
+    pub fn from_json(value: &Value) -> Result<Self, crate::errors::Error> {
+        let files = value
+            .get("files")
+            .and_then(Value::as_array)
+            .ok_or_else(|| invalid_field("files"))?
+            .iter()
+            .map(ManifestFile::from_json)
+            .collect::<Result<Vec<ManifestFile>, crate::errors::Error>>()?;
+        Ok(Self {
+            exported_at: value
+                .get("exported_at")
+                .and_then(Value::as_u64)
+                .ok_or_else(|| invalid_field("exported_at"))?,
+            schema_fingerprint: string_field(value, "schema_fingerprint")?.to_owned(),
+            number_of_nodes: usize_field(value, "number_of_nodes")?,
+            number_of_node_classes: usize_field(value, "number_of_node_classes")?,
+            number_of_edges: usize_field(value, "number_of_edges")?,
+            number_of_edge_classes: usize_field(value, "number_of_edge_classes")?,
+            files,
+            options: value.get("options").cloned().unwrap_or(Value::Null),
+            base_exported_at: value.get("base_exported_at").and_then(Value::as_u64),
+        })
+    }
+
+    /// Writes the manifest to the `manifest.json` file of the provided
+    /// directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The export directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the file fails.
+    pub fn write(&self, directory: &Path) -> Result<(), crate::errors::Error> {
+        let file = std::fs::File::create(directory.join(MANIFEST_FILE_NAME))?;
+        let mut writer = std::io::BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, &self.to_json())?;
+        // As for the CSV files, the file is flushed to disk before the export
+        // is published.
+        std::io::Write::flush(&mut writer)?;
+        writer.get_ref().sync_all()?;
+        Ok(())
+    }
+
+    /// Reads the manifest from the `manifest.json` file of the provided
+    /// directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The export directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading or parsing the file fails.
+    pub fn read(directory: &Path) -> Result<Self, crate::errors::Error> {
+        let file = std::fs::File::open(directory.join(MANIFEST_FILE_NAME))?;
+        let value: Value = serde_json::from_reader(std::io::BufReader::new(file))?;
+        Self::from_json(&value)
+    }
+}
+
+/// Returns the name of the graph exported to the provided directory.
+///
+/// # Arguments
+///
+/// * `directory` - The export directory.
+pub(crate) fn graph_name(directory: &Path) -> String {
+    directory.file_name().map_or_else(
+        || env!("CARGO_PKG_NAME").to_owned(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+/// Returns the error for a missing or malformed manifest field.
+///
+/// # Arguments
+///
+/// * `field` - The name of the field.
+fn invalid_field(field: &str) -> crate::errors::Error {
+    crate::errors::Error::InvalidManifest(format!("missing or malformed field `{field}`"))
+}
+
+/// Returns the string field with the provided name.
+///
+/// # Arguments
+///
+/// * `value` - The JSON object containing the field.
+/// * `field` - The name of the field.
+fn string_field<'a>(value: &'a Value, field: &str) -> Result<&'a str, crate::errors::Error> {
+    value.get(field).and_then(Value::as_str).ok_or_else(|| invalid_field(field))
+}
+
+/// Returns the non-negative integer field with the provided name.
+///
+/// # Arguments
+///
+/// * `value` - The JSON object containing the field.
+/// * `field` - The name of the field.
+fn usize_field(value: &Value, field: &str) -> Result<usize, crate::errors::Error> {
+    value
+        .get(field)
+        .and_then(Value::as_u64)
+        .and_then(|number| usize::try_from(number).ok())
+        .ok_or_else(|| invalid_field(field))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns an empty temporary directory for the provided test.
+    fn test_directory(test_name: &str) -> std::path::PathBuf {
+        let directory = std::env::temp_dir()
+            .join(format!("sql2kg-manifest-{}-{test_name}", std::process::id()));
+        if directory.exists() {
+            std::fs::remove_dir_all(&directory).unwrap();
+        }
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    /// Returns a manifest exported with the provided edge orientation.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_orientation` - The name of the edge orientation.
+    fn manifest(edge_orientation: &str) -> Manifest {
+        Manifest::new(
+            1_700_000_000,
+            "fingerprint".to_owned(),
+            vec![
+                ManifestFile::new("nodes.csv".to_owned(), 10, "a".to_owned()),
+                ManifestFile::new("node_classes.csv".to_owned(), 2, "b".to_owned()),
+            ],
+            json!({ "edge_orientation": edge_orientation }),
+        )
+    }
+
+    #[test]
+    fn test_grape_config_uses_absolute_paths() {
+        let directory = test_directory("grape");
+        let manifest = manifest(EdgeOrientation::Undirected.name());
+
+        // A relative directory is resolved against the working directory.
+        let relative = Path::new("kg_data").join(format!("sql2kg-{}", std::process::id()));
+        let arguments = manifest.grape_config(&relative).unwrap().to_json();
+        let expected = std::env::current_dir().unwrap().join(&relative).join("nodes.csv");
+        assert_eq!(arguments["node_path"], expected.to_string_lossy().as_ref());
+        assert_eq!(arguments["name"], format!("sql2kg-{}", std::process::id()));
+
+        // An existing directory is canonicalized, so that `.` names it.
+        let arguments = manifest.grape_config(&directory.join(".")).unwrap().to_json();
+        let expected = directory.canonicalize().unwrap().join("edges.csv");
+        assert_eq!(arguments["edge_path"], expected.to_string_lossy().as_ref());
+        assert_eq!(arguments["name"], directory.file_name().unwrap().to_string_lossy().as_ref());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_grape_config_orientation() {
+        let directory = test_directory("orientation");
+        let undirected = manifest(EdgeOrientation::Undirected.name());
+        let config = undirected.grape_config(&directory).unwrap();
+        assert!(!config.is_directed());
+        assert_eq!(config.to_json()["number_of_nodes"], 10);
+        assert_eq!(config.to_json()["number_of_node_types"], 2);
+        assert_eq!(config.to_json()["number_of_edge_types"], 0);
+
+        for edge_orientation in [EdgeOrientation::Directed.name(), "unknown"] {
+            assert!(manifest(edge_orientation).grape_config(&directory).unwrap().is_directed());
+        }
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_new_counts_rows() {
+        let manifest = Manifest::new(
+            1_700_000_000,
+            "fingerprint".to_owned(),
+            vec![
+                ManifestFile::new("node_classes.csv".to_owned(), 2, "a".to_owned()),
+                ManifestFile::new("nodes.csv".to_owned(), 10, "b".to_owned()),
+                ManifestFile::new("edge_classes.csv".to_owned(), 3, "c".to_owned()),
+                ManifestFile::new("edges.csv".to_owned(), 25, "d".to_owned()),
+            ],
+            json!({}),
+        );
+        assert_eq!(manifest.number_of_node_classes(), 2);
+        assert_eq!(manifest.number_of_nodes(), 10);
+        assert_eq!(manifest.number_of_edge_classes(), 3);
+        assert_eq!(manifest.number_of_edges(), 25);
+        assert_eq!(manifest.base_exported_at(), None);
+        assert_eq!(manifest.file("edges.csv").map(ManifestFile::sha256), Some("d"));
+        assert!(manifest.file("statistics.json").is_none());
+
+        // Missing files are counted as empty.
+        let manifest = Manifest::new(0, String::new(), Vec::new(), json!({}));
+        assert_eq!(manifest.number_of_nodes(), 0);
+        assert_eq!(manifest.number_of_edges(), 0);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let manifest = manifest(EdgeOrientation::Directed.name()).with_base_exported_at(42);
+        let value = manifest.to_json();
+        assert_eq!(value["format_version"], FORMAT_VERSION);
+        assert_eq!(value["base_exported_at"], 42);
+        assert_eq!(value["files"][0]["path"], "nodes.csv");
+        assert_eq!(Manifest::from_json(&value).unwrap(), manifest);
+
+        let mut value = value;
+        value.as_object_mut().unwrap().remove("number_of_nodes");
+        assert!(matches!(
+            Manifest::from_json(&value),
+            Err(crate::errors::Error::InvalidManifest(message)) if message.contains("number_of_nodes")
+        ));
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let directory = test_directory("write");
+        let manifest = manifest(EdgeOrientation::WithInverse.name());
+        manifest.write(&directory).unwrap();
+        assert!(directory.join(MANIFEST_FILE_NAME).is_file());
+        assert_eq!(Manifest::read(&directory).unwrap(), manifest);
+
+        std::fs::write(directory.join(MANIFEST_FILE_NAME), "{").unwrap();
+        assert!(matches!(Manifest::read(&directory), Err(crate::errors::Error::Json(_))));
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert!(matches!(Manifest::read(&directory), Err(crate::errors::Error::Io(_))));
+    }
+}
@@ -2,9 +2,14 @@
 
 use std::fmt::Display;
 
-use sql_traits::traits::{DatabaseLike, TableLike};
+use sql_traits::traits::{ColumnLike, DatabaseLike, TableLike};
 
-use crate::primary_key::PrimaryKey;
+use crate::{
+    descriptor::NodeDescriptor,
+    node_name::{ClassName, NodeName},
+    node_ref::NodeRef,
+    primary_key::PrimaryKey,
+};
 
 #[derive(Debug, Clone)]
 /// A struct representing a node-like entity in a knowledge graph.
@@ -62,6 +67,12 @@ impl<'db, DB: DatabaseLike> Node<'db, DB> {
         self.table
     }
 
+    /// Returns a reference to the primary key identifying the node.
+    #[must_use]
+    pub fn primary_key(&self) -> &PrimaryKey {
+        &self.primary_key
+    }
+
     /// Returns the name of the node's table.
     #[must_use]
     pub fn table_name(&self) -> &str {
@@ -73,13 +84,84 @@ impl<'db, DB: DatabaseLike> Node<'db, DB> {
     pub fn schema_name(&self) -> Option<&str> {
         self.table.table_schema()
     }
+
+    /// Returns the owned name of the node.
+    #[must_use]
+    pub fn name(&self) -> NodeName {
+        NodeName::new(
+            self.schema_name().map(str::to_owned),
+            self.table_name().to_owned(),
+            self.primary_key.clone(),
+        )
+    }
+
+    /// Returns the owned descriptor of the node.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The database containing the node's table, used to
+    ///   retrieve the primary key columns.
+    #[must_use]
+    pub fn descriptor(&self, database: &DB) -> NodeDescriptor {
+        NodeDescriptor::new(
+            self.schema_name().map(str::to_owned),
+            self.table_name().to_owned(),
+            self.table
+                .primary_key_columns(database)
+                .map(|column| column.column_name().to_owned())
+                .collect(),
+            self.primary_key.clone(),
+        )
+    }
+
+    /// Returns the owned reference to the node.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The database containing the node's table, used to
+    ///   retrieve the identifier of the node class.
+    #[must_use]
+    pub fn node_ref(&self, database: &DB) -> NodeRef {
+        NodeRef::new(
+            database.table_id(self.table).expect("Failed to find tables loaded from the database"),
+            self.primary_key.clone(),
+        )
+    }
+
+    /// Returns the node of the provided database identified by the provided
+    /// name, if its table exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The database containing the node's table.
+    /// * `name` - The name of the node.
+    ///
+    /// # Implementative details
+    ///
+    /// The existence of the row identified by the primary key is not checked,
+    /// and names of value nodes never resolve to a node.
+    #[must_use]
+    pub fn from_name(database: &'db DB, name: &NodeName) -> Option<Self> {
+        if name.column_name().is_some() {
+            return None;
+        }
+        database
+            .tables()
+            .find(|table| {
+                table.table_name() == name.table_name()
+                    && table.table_schema() == name.schema_name()
+            })
+            .map(|table| Self::new(table, name.primary_key().clone()))
+    }
 }
 
 impl<DB: DatabaseLike> Display for Node<'_, DB> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if let Some(schema) = self.table.table_schema() {
-            write!(f, "{schema}.")?;
-        }
-        write!(f, "{}({})", self.table.table_name(), self.primary_key)
+        write!(
+            f,
+            "{}({})",
+            ClassName { schema: self.table.table_schema(), table: self.table.table_name() },
+            self.primary_key
+        )
     }
 }
@@ -0,0 +1,171 @@
+//! Submodule defining the owned, parseable name of a node in a knowledge
+//! graph.
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::{parser::Parser, primary_key::PrimaryKey};
+
+/// A wrapper displaying an identifier, enclosing it in double quotes when it
+/// is not a simple identifier.
+pub(crate) struct Identifier<'a>(pub(crate) &'a str);
+
+impl Identifier<'_> {
+    /// Returns whether the identifier only contains ASCII alphanumeric
+    /// characters and underscores, and does not start with a digit.
+    fn is_simple(&self) -> bool {
+        self.0.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && self.0.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+}
+
+impl Display for Identifier<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_simple() {
+            write!(f, "{}", self.0)
+        } else {
+            write!(f, "\"{}\"", self.0.replace('"', "\"\""))
+        }
+    }
+}
+
+/// A wrapper displaying the name of the class of a table, i.e. its
+/// schema-qualified name.
+pub(crate) struct ClassName<'a> {
+    /// The schema of the table, if any.
+    pub(crate) schema: Option<&'a str>,
+    /// The name of the table.
+    pub(crate) table: &'a str,
+}
+
+impl Display for ClassName<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(schema) = self.schema {
+            write!(f, "{}.", Identifier(schema))?;
+        }
+        write!(f, "{}", Identifier(self.table))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// The owned name of a node, which can be displayed and parsed back.
+///
+/// The name of a row node is its schema-qualified table name followed by its
+/// primary key in parentheses, such as `public.users(1)`, while the name of
+/// a value node of a categorical column also includes the column, such as
+/// `public.orders(status = 'shipped')`. Identifiers which are not made of
+/// ASCII alphanumeric characters and underscores are enclosed in double
+/// quotes, so that distinct nodes always have distinct names.
+///
+/// # Example
+///
+/// ```rust
+/// use sql2kg::prelude::*;
+///
+/// let name: NodeName = "public.\"user notes\"('a''b', 2::bigint)".parse().unwrap();
+/// assert_eq!(name.schema_name(), Some("public"));
+/// assert_eq!(name.table_name(), "user notes");
+/// assert_eq!(
+///     name.primary_key(),
+///     &PrimaryKey::from(vec![PrimaryKey::from("a'b".to_owned()), PrimaryKey::from(2_i64)])
+/// );
+/// assert_eq!(name.to_string(), "public.\"user notes\"('a''b', 2::bigint)");
+/// ```
+pub struct NodeName {
+    /// The schema of the node's table, if any.
+    schema: Option<String>,
+    /// The name of the node's table.
+    table: String,
+    /// The categorical column of a value node, if any.
+    column: Option<String>,
+    /// The primary key of the node, or the value of a value node.
+    primary_key: PrimaryKey,
+}
+
+impl NodeName {
+    /// Create a new `NodeName` instance for a row node.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the node's table, if any.
+    /// * `table` - The name of the node's table.
+    /// * `primary_key` - The primary key of the node.
+    #[must_use]
+    pub fn new(schema: Option<String>, table: String, primary_key: PrimaryKey) -> Self {
+        Self { schema, table, column: None, primary_key }
+    }
+
+    /// Create a new `NodeName` instance for a value node of a categorical
+    /// column.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the column's table, if any.
+    /// * `table` - The name of the column's table.
+    /// * `column` - The name of the categorical column.
+    /// * `value` - The value of the node.
+    #[must_use]
+    pub fn value(schema: Option<String>, table: String, column: String, value: String) -> Self {
+        Self { schema, table, column: Some(column), primary_key: PrimaryKey::String(value) }
+    }
+
+    /// Returns the schema name of the node's table, if any.
+    #[must_use]
+    pub fn schema_name(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    /// Returns the name of the node's table.
+    #[must_use]
+    pub fn table_name(&self) -> &str {
+        &self.table
+    }
+
+    /// Returns the categorical column of a value node, if any.
+    #[must_use]
+    pub fn column_name(&self) -> Option<&str> {
+        self.column.as_deref()
+    }
+
+    /// Returns the primary key of the node, or the value of a value node.
+    #[must_use]
+    pub fn primary_key(&self) -> &PrimaryKey {
+        &self.primary_key
+    }
+}
+
+impl Display for NodeName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}(", ClassName { schema: self.schema.as_deref(), table: &self.table })?;
+        if let Some(column) = &self.column {
+            write!(f, "{} = ", Identifier(column))?;
+        }
+        write!(f, "{})", self.primary_key)
+    }
+}
+
+impl FromStr for NodeName {
+    type Err = crate::errors::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            |reason: String| crate::errors::Error::InvalidNodeName(format!("{s}: {reason}"));
+        let mut parser = Parser::new(s);
+        let first = parser.parse_identifier().map_err(invalid)?;
+        let (schema, table) = if parser.eat(".") {
+            (Some(first), parser.parse_identifier().map_err(invalid)?)
+        } else {
+            (None, first)
+        };
+        parser.expect("(").map_err(invalid)?;
+        let column = parser.try_parse_identifier_followed_by(" = ");
+        let primary_key = parser.parse_primary_key().map_err(invalid)?;
+        parser.expect(")").map_err(invalid)?;
+        if !parser.is_at_end() {
+            return Err(invalid("unexpected trailing characters".to_owned()));
+        }
+        if column.is_some() && !matches!(primary_key, PrimaryKey::String(_)) {
+            return Err(invalid("the value of a value node must be a string".to_owned()));
+        }
+        Ok(Self { schema, table, column, primary_key })
+    }
+}
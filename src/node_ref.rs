@@ -0,0 +1,87 @@
+//! Submodule defining an owned reference to a node, independent of the
+//! lifetime of the database schema.
+
+use sql_traits::traits::DatabaseLike;
+
+use crate::{node::Node, primary_key::PrimaryKey};
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An owned reference to a node, made of the identifier of its node class and
+/// its primary key.
+///
+/// Unlike [`Node`], a `NodeRef` does not borrow the database schema, and can
+/// therefore be stored beyond the lifetime of the schema or sent across
+/// threads. The node class identifier is the identifier of the node's table
+/// in the database, which is also its row in `node_classes.csv`.
+pub struct NodeRef {
+    /// The identifier of the node class of the node.
+    node_class_id: usize,
+    /// The primary key values identifying the node.
+    primary_key: PrimaryKey,
+}
+
+impl NodeRef {
+    /// Create a new `NodeRef` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_class_id` - The identifier of the node class of the node.
+    /// * `primary_key` - The primary key values identifying the node.
+    #[must_use]
+    pub fn new(node_class_id: usize, primary_key: PrimaryKey) -> Self {
+        Self { node_class_id, primary_key }
+    }
+
+    /// Returns the identifier of the node class of the node.
+    #[must_use]
+    pub fn node_class_id(&self) -> usize {
+        self.node_class_id
+    }
+
+    /// Returns a reference to the primary key values identifying the node.
+    #[must_use]
+    pub fn primary_key(&self) -> &PrimaryKey {
+        &self.primary_key
+    }
+
+    /// Returns the borrowed node corresponding to the reference, if the node
+    /// class exists in the provided database.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The database containing the node's table.
+    #[must_use]
+    pub fn resolve<'db, DB: DatabaseLike>(&self, database: &'db DB) -> Option<Node<'db, DB>> {
+        database
+            .tables()
+            .nth(self.node_class_id)
+            .map(|table| Node::new(table, self.primary_key.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sql_traits::{prelude::ParserDB, traits::TableLike};
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let db = ParserDB::try_from(
+            "CREATE TABLE users (id INT PRIMARY KEY);
+            CREATE TABLE posts (id INT PRIMARY KEY, author_id INT REFERENCES users (id));",
+        )
+        .unwrap();
+
+        for (node_class_id, table) in db.tables().enumerate() {
+            let node = Node::new(table, PrimaryKey::I32(1));
+            let node_ref = node.node_ref(&db);
+            assert_eq!(node_ref, NodeRef::new(node_class_id, PrimaryKey::I32(1)));
+            let resolved = node_ref.resolve(&db).unwrap();
+            assert_eq!(resolved, node);
+            assert_eq!(resolved.table().table_name(), table.table_name());
+        }
+        assert!(NodeRef::new(2, PrimaryKey::I32(1)).resolve(&db).is_none());
+    }
+}
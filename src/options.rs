@@ -0,0 +1,517 @@
+//! Submodule defining the options which can be used to customize the
+//! knowledge graph export.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{Value, json};
+
+use crate::{
+    cancellation::CancellationToken,
+    progress::{ExportPhase, Progress, ProgressObserver, SharedProgressObserver},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// Policy describing how to handle foreign key values which reference a row
+/// that does not exist in the referenced table.
+///
+/// Such dangling references can only occur when the foreign key constraints
+/// are deferred or disabled, but when they do, the referenced node does not
+/// appear in the node list.
+pub enum DanglingReferencePolicy {
+    /// Abort the export with a `DanglingReference` error.
+    #[default]
+    Fail,
+    /// Skip the edge and count it in the export report.
+    Skip,
+    /// Create a placeholder node of the referenced node class, append it to
+    /// the node list and count it in the export report.
+    ///
+    /// For foreign keys referencing unique columns other than the primary
+    /// key, the primary key of the missing row is unknown, hence the edge is
+    /// skipped and counted as with [`DanglingReferencePolicy::Skip`].
+    Placeholder,
+}
+
+impl DanglingReferencePolicy {
+    /// Returns the name of the policy.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            DanglingReferencePolicy::Fail => "fail",
+            DanglingReferencePolicy::Skip => "skip",
+            DanglingReferencePolicy::Placeholder => "placeholder",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// How the edges of the knowledge graph are oriented in the export.
+pub enum EdgeOrientation {
+    /// Each edge points from the referencing row to the referenced row.
+    #[default]
+    Directed,
+    /// Each edge is followed by its reverse edge, whose edge class is the
+    /// inverse of the original one, such as `posts(author_id) -> users(id)^-1`.
+    WithInverse,
+    /// The edges are written as in [`EdgeOrientation::Directed`], but the
+    /// export is flagged as undirected for the loaders.
+    Undirected,
+}
+
+impl EdgeOrientation {
+    /// Returns the name of the orientation.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            EdgeOrientation::Directed => "directed",
+            EdgeOrientation::WithInverse => "with_inverse",
+            EdgeOrientation::Undirected => "undirected",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// Options customizing the export of the knowledge graph.
+pub struct ExportOptions {
+    /// How to handle foreign key values referencing missing rows.
+    dangling_reference_policy: DanglingReferencePolicy,
+    /// The columns exported as edge properties, indexed by table name.
+    edge_properties: BTreeMap<String, Vec<String>>,
+    /// The columns whose values are exported as value nodes, as pairs of
+    /// table name and column name.
+    categorical_columns: BTreeSet<(String, String)>,
+    /// The columns recording the last modification of the rows, indexed by
+    /// table name.
+    modification_columns: BTreeMap<String, String>,
+    /// Whether to resume from the checkpoint left by an interrupted export.
+    resume: bool,
+    /// Whether to keep the previous export as a backup.
+    keep_backup: bool,
+    /// The identifier of an exported snapshot to read the database from.
+    snapshot: Option<String>,
+    /// The observer the progress of the export is reported to.
+    progress_observer: Option<SharedProgressObserver>,
+    /// The token through which the export can be cancelled.
+    cancellation_token: Option<CancellationToken>,
+    /// Whether to estimate the row counts from the planner statistics.
+    estimated_row_counts: bool,
+    /// Whether to compute the per-class statistics of the export.
+    statistics: bool,
+    /// How the edges are oriented in the export.
+    edge_orientation: EdgeOrientation,
+}
+
+impl ExportOptions {
+    /// Creates a new `ExportOptions` instance with the default options.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the policy used to handle dangling foreign key references.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy to use.
+    #[must_use]
+    pub fn with_dangling_reference_policy(mut self, policy: DanglingReferencePolicy) -> Self {
+        self.dangling_reference_policy = policy;
+        self
+    }
+
+    /// Returns the policy used to handle dangling foreign key references.
+    #[must_use]
+    pub fn dangling_reference_policy(&self) -> DanglingReferencePolicy {
+        self.dangling_reference_policy
+    }
+
+    /// Sets the columns of the provided table to be exported as properties of
+    /// the edges originating from the rows of the table.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the table hosting the foreign keys, which
+    ///   must be qualified with its schema, such as `public.orders`, when
+    ///   tables of several schemas share it.
+    /// * `column_names` - The names of the columns to export as properties.
+    ///
+    /// # Implementative details
+    ///
+    /// The properties are written to `edge_properties.csv`, with one row per
+    /// non-null property value, referencing the zero-based row of the edge in
+    /// `edges.csv`.
+    #[must_use]
+    pub fn with_edge_properties<S: Into<String>>(
+        mut self,
+        table_name: impl Into<String>,
+        column_names: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.edge_properties
+            .insert(table_name.into(), column_names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Returns the names of the columns exported as edge properties for the
+    /// provided table, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the table hosting the foreign keys.
+    #[must_use]
+    pub fn edge_properties(&self, table_name: &str) -> Option<&[String]> {
+        self.edge_properties.get(table_name).map(Vec::as_slice)
+    }
+
+    /// Returns whether any edge property has been configured.
+    #[must_use]
+    pub fn has_edge_properties(&self) -> bool {
+        !self.edge_properties.is_empty()
+    }
+
+    /// Returns an iterator over the names of the tables for which edge
+    /// properties have been configured.
+    pub fn edge_property_tables(&self) -> impl Iterator<Item = &str> {
+        self.edge_properties.keys().map(String::as_str)
+    }
+
+    /// Sets the provided column to be exported as a categorical column.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the table containing the column, optionally
+    ///   qualified with its schema.
+    /// * `column_name` - The name of the categorical column.
+    ///
+    /// # Implementative details
+    ///
+    /// Each distinct non-null value of a categorical column becomes a value
+    /// node of a synthetic node class named after the column, such as
+    /// `orders.status`, and each row with a non-null value is connected to its
+    /// value node by an edge of an edge class named after the column, such as
+    /// `orders(status)`. The values are read as text, so that columns of any
+    /// type with a textual representation, including enumerated types, can
+    /// be categorical. The table must have a primary key.
+    #[must_use]
+    pub fn with_categorical_column(
+        mut self,
+        table_name: impl Into<String>,
+        column_name: impl Into<String>,
+    ) -> Self {
+        self.categorical_columns.insert((table_name.into(), column_name.into()));
+        self
+    }
+
+    /// Returns an iterator over the categorical columns, as pairs of table
+    /// name and column name.
+    pub fn categorical_columns(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.categorical_columns
+            .iter()
+            .map(|(table_name, column_name)| (table_name.as_str(), column_name.as_str()))
+    }
+
+    /// Sets the column recording the last modification of the rows of the
+    /// provided table, such as `updated_at` or `date_updated`.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the table, optionally qualified with its
+    ///   schema.
+    /// * `column_name` - The name of the timestamp column.
+    ///
+    /// # Implementative details
+    ///
+    /// Only the tables with a modification column are queried by
+    /// incremental exports, which select the rows modified since the
+    /// previous export. In an inheritance hierarchy, each table needs its own
+    /// modification column.
+    #[must_use]
+    pub fn with_modification_column(
+        mut self,
+        table_name: impl Into<String>,
+        column_name: impl Into<String>,
+    ) -> Self {
+        self.modification_columns.insert(table_name.into(), column_name.into());
+        self
+    }
+
+    /// Returns the column recording the last modification of the rows of
+    /// the provided table, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the table.
+    #[must_use]
+    pub fn modification_column(&self, table_name: &str) -> Option<&str> {
+        self.modification_columns.get(table_name).map(String::as_str)
+    }
+
+    /// Returns an iterator over the modification columns, as pairs of table
+    /// name and column name.
+    pub fn modification_columns(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.modification_columns
+            .iter()
+            .map(|(table_name, column_name)| (table_name.as_str(), column_name.as_str()))
+    }
+
+    /// Sets whether to resume from the checkpoint left by an interrupted
+    /// export to the same path, instead of restarting the export from
+    /// scratch.
+    ///
+    /// # Arguments
+    ///
+    /// * `resume` - Whether to resume an interrupted export.
+    ///
+    /// # Implementative details
+    ///
+    /// A resumed export keeps the nodes and edges written by the previous
+    /// runs, which were read from older snapshots of the database, hence
+    /// resuming is only safe when the data did not change in the meantime.
+    /// Without this option, any checkpoint and partial file left in the
+    /// staging directory are discarded.
+    #[must_use]
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Returns whether the checkpoint left by an interrupted export is
+    /// resumed.
+    #[must_use]
+    pub fn resumes(&self) -> bool {
+        self.resume
+    }
+
+    /// Sets whether to keep the previous export to the same path as a
+    /// backup in the `{name}.backup` sibling directory, replacing any older
+    /// backup, instead of removing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `keep_backup` - Whether to keep the previous export as a backup.
+    #[must_use]
+    pub fn with_backup(mut self, keep_backup: bool) -> Self {
+        self.keep_backup = keep_backup;
+        self
+    }
+
+    /// Returns whether the previous export is kept as a backup.
+    #[must_use]
+    pub fn keeps_backup(&self) -> bool {
+        self.keep_backup
+    }
+
+    /// Sets the identifier of a snapshot exported by another transaction,
+    /// as returned by `pg_export_snapshot()`, from which to read the
+    /// database.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The identifier of the exported snapshot.
+    ///
+    /// # Implementative details
+    ///
+    /// The export always runs within a single `REPEATABLE READ`, read-only
+    /// transaction, so that all of its queries see the same snapshot. By
+    /// importing a snapshot exported by a transaction which is kept open,
+    /// several exports, possibly running in parallel on different
+    /// connections, can share the same snapshot.
+    ///
+    /// A snapshot can only be imported by the first statement of a
+    /// transaction, hence an export with a snapshot fails with
+    /// [`Error::SnapshotWithinTransaction`](crate::errors::Error::SnapshotWithinTransaction)
+    /// when the connection is already within a transaction, and with
+    /// [`Error::SnapshotImport`](crate::errors::Error::SnapshotImport) when
+    /// the transaction which exported the snapshot has ended.
+    #[must_use]
+    pub fn with_snapshot(mut self, snapshot: impl Into<String>) -> Self {
+        self.snapshot = Some(snapshot.into());
+        self
+    }
+
+    /// Returns the identifier of the exported snapshot to read the database
+    /// from, if any.
+    #[must_use]
+    pub fn snapshot(&self) -> Option<&str> {
+        self.snapshot.as_deref()
+    }
+
+    /// Sets the observer the progress of the export is reported to, when
+    /// each phase starts, after each batch of rows and after each table or
+    /// edge class is written.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - The observer of the progress of the export.
+    #[must_use]
+    pub fn with_progress_observer(
+        mut self,
+        observer: impl ProgressObserver + Send + Sync + 'static,
+    ) -> Self {
+        self.progress_observer = Some(SharedProgressObserver(std::sync::Arc::new(observer)));
+        self
+    }
+
+    /// Returns the observer the progress of the export is reported to, if
+    /// any.
+    #[must_use]
+    pub fn progress_observer(&self) -> Option<&(dyn ProgressObserver + Send + Sync)> {
+        self.progress_observer.as_ref().map(|observer| observer.0.as_ref())
+    }
+
+    /// Reports the provided progress to the progress observer, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `phase` - The current phase of the export.
+    /// * `class` - The table or edge class being written, if any.
+    /// * `rows` - The number of rows written so far in the current phase.
+    /// * `expected_rows` - The number of rows expected in the current phase, if
+    ///   known.
+    pub(crate) fn report_progress(
+        &self,
+        phase: ExportPhase,
+        class: Option<&dyn std::fmt::Display>,
+        rows: usize,
+        expected_rows: Option<usize>,
+    ) {
+        if let Some(observer) = self.progress_observer() {
+            observer.on_progress(&Progress::new(
+                phase,
+                class.map(ToString::to_string),
+                rows,
+                expected_rows,
+            ));
+        }
+    }
+
+    /// Sets the token through which the export can be cancelled.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The cancellation token, a clone of which is kept to cancel
+    ///   the export.
+    #[must_use]
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Returns the token through which the export can be cancelled, if any.
+    #[must_use]
+    pub fn cancellation_token(&self) -> Option<&CancellationToken> {
+        self.cancellation_token.as_ref()
+    }
+
+    /// Returns an error if the export was cancelled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Cancelled`](crate::errors::Error::Cancelled) if the
+    /// cancellation token has been cancelled.
+    pub(crate) fn check_cancelled(&self) -> Result<(), crate::errors::Error> {
+        if self.cancellation_token.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            return Err(crate::errors::Error::Cancelled);
+        }
+        Ok(())
+    }
+
+    /// Sets whether to estimate the number of rows of the tables from the
+    /// planner statistics instead of counting them.
+    ///
+    /// # Arguments
+    ///
+    /// * `estimated_row_counts` - Whether to estimate the row counts.
+    ///
+    /// # Implementative details
+    ///
+    /// The number of rows is only used to pre-allocate the node list and as
+    /// the expected total reported to the progress observer, hence the
+    /// exported graph does not depend on it. Counting the rows exactly scans
+    /// every table, while the estimate is read from `pg_class.reltuples`, see
+    /// [`estimated_number_of_nodes`](crate::traits::KGLikeDB::estimated_number_of_nodes).
+    #[must_use]
+    pub fn with_estimated_row_counts(mut self, estimated_row_counts: bool) -> Self {
+        self.estimated_row_counts = estimated_row_counts;
+        self
+    }
+
+    /// Returns whether the number of rows of the tables is estimated from the
+    /// planner statistics.
+    #[must_use]
+    pub fn estimates_row_counts(&self) -> bool {
+        self.estimated_row_counts
+    }
+
+    /// Sets whether to compute the per-class statistics of the export, which
+    /// are returned in the export report and written to the
+    /// `statistics.json` file of the export directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `statistics` - Whether to compute the statistics.
+    ///
+    /// # Implementative details
+    ///
+    /// Counting the rows skipped because of null values requires one more
+    /// query per edge class, hence the statistics are not computed by
+    /// default.
+    #[must_use]
+    pub fn with_statistics(mut self, statistics: bool) -> Self {
+        self.statistics = statistics;
+        self
+    }
+
+    /// Returns whether the per-class statistics of the export are computed.
+    #[must_use]
+    pub fn computes_statistics(&self) -> bool {
+        self.statistics
+    }
+
+    /// Sets how the edges are oriented in the export.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_orientation` - The orientation of the edges.
+    ///
+    /// # Implementative details
+    ///
+    /// With [`EdgeOrientation::WithInverse`], the inverse edge classes
+    /// follow all of the other edge classes in `edge_classes.csv`, in the
+    /// same order and with their node classes swapped, and each edge is
+    /// immediately followed by its reverse edge in `edges.csv`. The edge
+    /// properties are only attached to the original edges.
+    #[must_use]
+    pub fn with_edge_orientation(mut self, edge_orientation: EdgeOrientation) -> Self {
+        self.edge_orientation = edge_orientation;
+        self
+    }
+
+    /// Returns how the edges are oriented in the export.
+    #[must_use]
+    pub fn edge_orientation(&self) -> EdgeOrientation {
+        self.edge_orientation
+    }
+
+    /// Returns the JSON representation of the options, as recorded in the
+    /// export manifest.
+    ///
+    /// # Implementative details
+    ///
+    /// Whether to resume the export, whether to keep a backup, the snapshot
+    /// to read from, the progress observer, the cancellation token, whether
+    /// to estimate the row counts and whether to compute the statistics are
+    /// not part of the representation, as they do not affect the shape of
+    /// the exported graph.
+    pub(crate) fn to_json(&self) -> Value {
+        json!({
+            "dangling_reference_policy": self.dangling_reference_policy.name(),
+            "edge_properties": self.edge_properties,
+            "categorical_columns": self
+                .categorical_columns()
+                .map(|(table_name, column_name)| json!({ "table": table_name, "column": column_name }))
+                .collect::<Vec<Value>>(),
+            "modification_columns": self.modification_columns,
+            "edge_orientation": self.edge_orientation.name(),
+        })
+    }
+}
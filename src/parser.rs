@@ -0,0 +1,239 @@
+//! Submodule providing the parser of node names and primary keys.
+
+use crate::primary_key::PrimaryKey;
+
+/// A cursor-based parser of node names and primary keys.
+pub(crate) struct Parser<'a> {
+    /// The input being parsed.
+    input: &'a str,
+    /// The byte offset of the next character to parse.
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Create a new `Parser` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input to parse.
+    pub(crate) fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    /// Returns the portion of the input which has not been parsed yet.
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    /// Returns whether the whole input has been parsed.
+    pub(crate) fn is_at_end(&self) -> bool {
+        self.position == self.input.len()
+    }
+
+    /// Consumes the provided token if the rest of the input starts with it,
+    /// returning whether it was consumed.
+    pub(crate) fn eat(&mut self, token: &str) -> bool {
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes the provided token, failing if the rest of the input does not
+    /// start with it.
+    pub(crate) fn expect(&mut self, token: &str) -> Result<(), String> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(format!("expected `{token}` at position {}", self.position))
+        }
+    }
+
+    /// Parses an identifier, either a simple one or a double-quoted one with
+    /// doubled double quotes.
+    pub(crate) fn parse_identifier(&mut self) -> Result<String, String> {
+        if self.eat("\"") {
+            return self.parse_quoted('"');
+        }
+        let length = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(self.rest().len());
+        if length == 0 {
+            return Err(format!("expected an identifier at position {}", self.position));
+        }
+        let identifier = self.rest()[..length].to_owned();
+        self.position += length;
+        Ok(identifier)
+    }
+
+    /// Parses an identifier followed by the provided token, restoring the
+    /// position and returning `None` if the input does not match.
+    pub(crate) fn try_parse_identifier_followed_by(&mut self, token: &str) -> Option<String> {
+        let position = self.position;
+        if let Ok(identifier) = self.parse_identifier()
+            && self.eat(token)
+        {
+            return Some(identifier);
+        }
+        self.position = position;
+        None
+    }
+
+    /// Parses the rest of a quoted literal, after its opening quote.
+    fn parse_quoted(&mut self, quote: char) -> Result<String, String> {
+        let mut value = String::new();
+        loop {
+            let Some(end) = self.rest().find(quote) else {
+                return Err(format!("unterminated literal starting before {}", self.position));
+            };
+            value.push_str(&self.rest()[..end]);
+            self.position += end + quote.len_utf8();
+            if self.rest().starts_with(quote) {
+                value.push(quote);
+                self.position += quote.len_utf8();
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Parses a primary key, i.e. one or more comma-separated primary key
+    /// values.
+    pub(crate) fn parse_primary_key(&mut self) -> Result<PrimaryKey, String> {
+        let mut values = vec![self.parse_primary_key_value()?];
+        while self.eat(", ") {
+            values.push(self.parse_primary_key_value()?);
+        }
+        Ok(values.into())
+    }
+
+    /// Parses a single primary key value.
+    fn parse_primary_key_value(&mut self) -> Result<PrimaryKey, String> {
+        if self.eat("'") {
+            return self.parse_quoted('\'').map(PrimaryKey::String);
+        }
+        if self.eat("(") {
+            let values = self.parse_primary_key()?;
+            self.expect(")")?;
+            return Ok(match values {
+                PrimaryKey::Composite(values) => PrimaryKey::Composite(values),
+                value => PrimaryKey::Composite(vec![value]),
+            });
+        }
+        let length = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .unwrap_or(self.rest().len());
+        let token = &self.rest()[..length];
+        let start = self.position;
+        self.position += length;
+        if self.eat("::bigint") {
+            return token.parse::<i64>().map(PrimaryKey::I64).map_err(|e| e.to_string());
+        }
+        if let Ok(value) = token.parse::<i32>() {
+            return Ok(PrimaryKey::I32(value));
+        }
+        // Only the lowercase hyphenated form is accepted, as it is the one
+        // displayed, so that each UUID has a single textual representation.
+        uuid::Uuid::parse_str(token)
+            .ok()
+            .filter(|uuid| uuid.to_string() == token)
+            .map(PrimaryKey::UUID)
+            .ok_or_else(|| format!("invalid primary key value `{token}` at position {start}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{node_name::NodeName, primary_key::PrimaryKey};
+
+    #[test]
+    fn test_nested_composite_primary_key() {
+        let primary_key = PrimaryKey::Composite(vec![
+            PrimaryKey::I32(1),
+            PrimaryKey::Composite(vec![PrimaryKey::I32(2), PrimaryKey::String("a".to_owned())]),
+        ]);
+        assert_eq!(primary_key.to_string(), "1, (2, 'a')");
+        assert_eq!(PrimaryKey::from_str("1, (2, 'a')").unwrap(), primary_key);
+        assert_eq!(PrimaryKey::from_str("(1, (2, 'a'))").unwrap(), primary_key);
+    }
+
+    #[test]
+    fn test_integer_primary_keys() {
+        assert_eq!(PrimaryKey::from_str("-42").unwrap(), PrimaryKey::I32(-42));
+        assert_eq!(PrimaryKey::from_str("42::bigint").unwrap(), PrimaryKey::I64(42));
+        assert_eq!(
+            PrimaryKey::from_str("-9223372036854775808::bigint").unwrap(),
+            PrimaryKey::I64(i64::MIN)
+        );
+        assert_eq!(
+            PrimaryKey::I64(-7).to_string().parse::<PrimaryKey>().unwrap(),
+            PrimaryKey::I64(-7)
+        );
+        assert!(PrimaryKey::from_str("2147483648").is_err());
+    }
+
+    #[test]
+    fn test_uuid_primary_key() {
+        let uuid = uuid::Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef);
+        let primary_key = PrimaryKey::UUID(uuid);
+        assert_eq!(primary_key.to_string(), "01234567-89ab-cdef-0123-456789abcdef");
+        assert_eq!(PrimaryKey::from_str(&primary_key.to_string()).unwrap(), primary_key);
+        for other_form in [
+            "0123456789abcdef0123456789abcdef",
+            "01234567-89AB-CDEF-0123-456789ABCDEF",
+            "0123456789-ab-cdef-0123-456789abcdef",
+        ] {
+            assert!(PrimaryKey::from_str(other_form).is_err(), "{other_form}");
+        }
+    }
+
+    #[test]
+    fn test_quoted_identifiers() {
+        let node = NodeName::new(
+            Some("my.schema".to_owned()),
+            "user\"s".to_owned(),
+            PrimaryKey::String("it's".to_owned()),
+        );
+        assert_eq!(node.to_string(), "\"my.schema\".\"user\"\"s\"('it''s')");
+        assert_eq!(NodeName::from_str(&node.to_string()).unwrap(), node);
+    }
+
+    #[test]
+    fn test_value_node_name() {
+        let node = NodeName::value(
+            Some("public".to_owned()),
+            "orders".to_owned(),
+            "status".to_owned(),
+            "shipped".to_owned(),
+        );
+        assert_eq!(node.to_string(), "public.orders(status = 'shipped')");
+        let parsed = NodeName::from_str(&node.to_string()).unwrap();
+        assert_eq!(parsed, node);
+        assert_eq!(parsed.column_name(), Some("status"));
+        assert!(NodeName::from_str("public.orders(status = 1)").is_err());
+    }
+
+    #[test]
+    fn test_trailing_characters_are_rejected() {
+        assert!(PrimaryKey::from_str("1 x").is_err());
+        assert!(PrimaryKey::from_str("'a'b").is_err());
+        assert!(PrimaryKey::from_str("1,").is_err());
+        assert!(NodeName::from_str("users(1) ").is_err());
+        assert!(NodeName::from_str("users(1)(2)").is_err());
+        assert!(NodeName::from_str("users(1").is_err());
+    }
+
+    #[test]
+    fn test_single_value_composite_primary_key() {
+        let primary_key = PrimaryKey::Composite(vec![PrimaryKey::I32(1)]);
+        assert_eq!(primary_key.to_string(), "1");
+        assert_eq!(PrimaryKey::from_str("1").unwrap(), PrimaryKey::I32(1));
+        assert_eq!(PrimaryKey::from(vec![PrimaryKey::I32(1)]), PrimaryKey::I32(1));
+    }
+}
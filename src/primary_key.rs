@@ -1,7 +1,7 @@
 //! Submodule defining what are valid primary key-like constructs in a
 //! knowledge graph-like database.
 
-use std::{fmt::Display, num::NonZeroU32};
+use std::{fmt::Display, num::NonZeroU32, str::FromStr};
 
 use diesel::{
     deserialize::{self, FromSql},
@@ -9,9 +9,33 @@ use diesel::{
 };
 use diesel_dynamic_schema::dynamic_value::Any;
 
+use crate::parser::Parser;
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value", rename_all = "snake_case"))]
 /// An enum representing valid primary key-like constructs in a knowledge
 /// graph-like database.
+///
+/// The textual representation of a primary key is unambiguous and can be
+/// parsed back with [`FromStr`]: strings are enclosed in single quotes with
+/// doubled inner single quotes, 64-bit integers are suffixed with `::bigint`,
+/// UUIDs are in their lowercase hyphenated form, and the values of composite
+/// keys are separated by `, `, with nested composite keys enclosed in
+/// parentheses.
+///
+/// # Example
+///
+/// ```rust
+/// use sql2kg::prelude::*;
+///
+/// let string_key = PrimaryKey::from("1, 2".to_owned());
+/// let composite_key = PrimaryKey::from(vec![PrimaryKey::from(1_i32), PrimaryKey::from(2_i64)]);
+/// assert_eq!(string_key.to_string(), "'1, 2'");
+/// assert_eq!(composite_key.to_string(), "1, 2::bigint");
+/// assert_eq!(string_key.to_string().parse::<PrimaryKey>().unwrap(), string_key);
+/// assert_eq!(composite_key.to_string().parse::<PrimaryKey>().unwrap(), composite_key);
+/// ```
 pub enum PrimaryKey {
     /// A string primary key value.
     String(String),
@@ -20,16 +44,44 @@ pub enum PrimaryKey {
     /// An integer (64-bit) primary key value.
     I64(i64),
     /// A UUID primary key value.
+    // The snake case of `UUID` would otherwise be `u_u_i_d`.
+    #[cfg_attr(feature = "serde", serde(rename = "uuid"))]
     UUID(uuid::Uuid),
     /// A composite primary key value.
+    ///
+    /// A composite of a single value is displayed as that value, and is
+    /// therefore parsed back as the value itself. Composite keys should be
+    /// built through the `From<Vec<PrimaryKey>>` implementation, which
+    /// collapses a single value in the same way.
     Composite(Vec<PrimaryKey>),
 }
 
-const VARCHAR_OID: NonZeroU32 = NonZeroU32::new(1043).expect("OID must be non-zero");
-const TEXT_OID: NonZeroU32 = NonZeroU32::new(25).expect("OID must be non-zero");
-const INTEGER_OID: NonZeroU32 = NonZeroU32::new(23).expect("OID must be non-zero");
-const BIGINT_OID: NonZeroU32 = NonZeroU32::new(20).expect("OID must be non-zero");
-const UUID_OID: NonZeroU32 = NonZeroU32::new(2950).expect("OID must be non-zero");
+pub(crate) const BOOL_OID: NonZeroU32 = NonZeroU32::new(16).expect("OID must be non-zero");
+pub(crate) const BIGINT_OID: NonZeroU32 = NonZeroU32::new(20).expect("OID must be non-zero");
+pub(crate) const SMALLINT_OID: NonZeroU32 = NonZeroU32::new(21).expect("OID must be non-zero");
+pub(crate) const INTEGER_OID: NonZeroU32 = NonZeroU32::new(23).expect("OID must be non-zero");
+pub(crate) const TEXT_OID: NonZeroU32 = NonZeroU32::new(25).expect("OID must be non-zero");
+pub(crate) const FLOAT4_OID: NonZeroU32 = NonZeroU32::new(700).expect("OID must be non-zero");
+pub(crate) const FLOAT8_OID: NonZeroU32 = NonZeroU32::new(701).expect("OID must be non-zero");
+pub(crate) const BPCHAR_OID: NonZeroU32 = NonZeroU32::new(1042).expect("OID must be non-zero");
+pub(crate) const VARCHAR_OID: NonZeroU32 = NonZeroU32::new(1043).expect("OID must be non-zero");
+pub(crate) const DATE_OID: NonZeroU32 = NonZeroU32::new(1082).expect("OID must be non-zero");
+pub(crate) const TIMESTAMP_OID: NonZeroU32 = NonZeroU32::new(1114).expect("OID must be non-zero");
+pub(crate) const TIMESTAMPTZ_OID: NonZeroU32 = NonZeroU32::new(1184).expect("OID must be non-zero");
+pub(crate) const UUID_OID: NonZeroU32 = NonZeroU32::new(2950).expect("OID must be non-zero");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The error returned when deserializing a value whose type is not supported,
+/// carrying the OID of the type so that the column can be identified.
+pub(crate) struct UnsupportedOid(pub(crate) NonZeroU32);
+
+impl Display for UnsupportedOid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown type: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnsupportedOid {}
 
 impl FromSql<Any, Pg> for PrimaryKey {
     fn from_sql(value: PgValue) -> deserialize::Result<Self> {
@@ -50,7 +102,7 @@ impl FromSql<Any, Pg> for PrimaryKey {
                 <uuid::Uuid as FromSql<diesel::sql_types::Uuid, Pg>>::from_sql(value)
                     .map(PrimaryKey::UUID)
             }
-            e => Err(format!("Unknown type: {e}").into()),
+            oid => Err(Box::new(UnsupportedOid(oid))),
         }
     }
 }
@@ -91,14 +143,75 @@ impl From<Vec<PrimaryKey>> for PrimaryKey {
 impl Display for PrimaryKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            PrimaryKey::String(s) => write!(f, "{s}"),
+            PrimaryKey::String(s) => write!(f, "'{}'", s.replace('\'', "''")),
             PrimaryKey::I32(i) => write!(f, "{i}"),
-            PrimaryKey::I64(i) => write!(f, "{i}"),
+            PrimaryKey::I64(i) => write!(f, "{i}::bigint"),
             PrimaryKey::UUID(u) => write!(f, "{u}"),
             PrimaryKey::Composite(pk_vec) => {
-                let pk_strings: Vec<String> = pk_vec.iter().map(|pk| format!("{pk}")).collect();
-                write!(f, "{}", pk_strings.join(", "))
+                for (i, pk) in pk_vec.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    // Nested composite keys are parenthesized so that their
+                    // boundaries can be recovered when parsing.
+                    if matches!(pk, PrimaryKey::Composite(_)) {
+                        write!(f, "({pk})")?;
+                    } else {
+                        write!(f, "{pk}")?;
+                    }
+                }
+                Ok(())
             }
         }
     }
 }
+
+impl FromStr for PrimaryKey {
+    type Err = crate::errors::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let primary_key = parser
+            .parse_primary_key()
+            .map_err(|reason| crate::errors::Error::InvalidPrimaryKey(format!("{s}: {reason}")))?;
+        if !parser.is_at_end() {
+            return Err(crate::errors::Error::InvalidPrimaryKey(format!(
+                "{s}: unexpected trailing characters"
+            )));
+        }
+        Ok(primary_key)
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_serde_round_trip() {
+        let uuid = uuid::Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef);
+        let primary_key = PrimaryKey::Composite(vec![
+            PrimaryKey::String("a".to_owned()),
+            PrimaryKey::Composite(vec![PrimaryKey::I32(1), PrimaryKey::I64(2)]),
+            PrimaryKey::UUID(uuid),
+        ]);
+        let value = serde_json::to_value(&primary_key).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "type": "composite",
+                "value": [
+                    { "type": "string", "value": "a" },
+                    {
+                        "type": "composite",
+                        "value": [{ "type": "i32", "value": 1 }, { "type": "i64", "value": 2 }],
+                    },
+                    { "type": "uuid", "value": "01234567-89ab-cdef-0123-456789abcdef" },
+                ],
+            })
+        );
+        assert_eq!(serde_json::from_value::<PrimaryKey>(value).unwrap(), primary_key);
+    }
+}
@@ -0,0 +1,155 @@
+//! Submodule defining the progress reported while exporting a knowledge
+//! graph, and the observers it is reported to.
+
+use std::{fmt::Display, sync::Arc};
+
+/// The number of rows written between two progress reports within the same
+/// table or edge class.
+pub(crate) const PROGRESS_BATCH_SIZE: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// The phases of a knowledge graph export, in the order in which they run.
+pub enum ExportPhase {
+    /// Writing the node classes.
+    NodeClasses,
+    /// Writing the nodes read from the tables of the database.
+    Nodes,
+    /// Writing the value nodes of the categorical columns.
+    ValueNodes,
+    /// Writing the edge classes.
+    EdgeClasses,
+    /// Writing the edges of the foreign keys.
+    Edges,
+    /// Writing the edges towards the value nodes of the categorical columns.
+    ValueEdges,
+    /// Writing the manifest and moving the export into place.
+    Publishing,
+}
+
+impl ExportPhase {
+    /// Returns the name of the phase.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportPhase::NodeClasses => "node_classes",
+            ExportPhase::Nodes => "nodes",
+            ExportPhase::ValueNodes => "value_nodes",
+            ExportPhase::EdgeClasses => "edge_classes",
+            ExportPhase::Edges => "edges",
+            ExportPhase::ValueEdges => "value_edges",
+            ExportPhase::Publishing => "publishing",
+        }
+    }
+}
+
+impl Display for ExportPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The progress of an export, reported to the [`ProgressObserver`] of the
+/// export options.
+pub struct Progress {
+    /// The current phase of the export.
+    phase: ExportPhase,
+    /// The name of the table or edge class being written, if any.
+    class: Option<String>,
+    /// The number of rows written so far in the current phase.
+    rows: usize,
+    /// The number of rows expected in the current phase, if known.
+    expected_rows: Option<usize>,
+}
+
+impl Progress {
+    /// Create a new `Progress` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `phase` - The current phase of the export.
+    /// * `class` - The name of the table or edge class being written, if any.
+    /// * `rows` - The number of rows written so far in the current phase.
+    /// * `expected_rows` - The number of rows expected in the current phase, if
+    ///   known.
+    pub(crate) fn new(
+        phase: ExportPhase,
+        class: Option<String>,
+        rows: usize,
+        expected_rows: Option<usize>,
+    ) -> Self {
+        Self { phase, class, rows, expected_rows }
+    }
+
+    /// Returns the current phase of the export.
+    #[must_use]
+    pub fn phase(&self) -> ExportPhase {
+        self.phase
+    }
+
+    /// Returns the name of the table or edge class being written, if any.
+    #[must_use]
+    pub fn class(&self) -> Option<&str> {
+        self.class.as_deref()
+    }
+
+    /// Returns the number of rows written so far in the current phase.
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of rows expected in the current phase, if known.
+    ///
+    /// # Implementative details
+    ///
+    /// The number of nodes is counted ahead of the export, or estimated when
+    /// requested with
+    /// [`ExportOptions::with_estimated_row_counts`](crate::options::ExportOptions::with_estimated_row_counts),
+    /// in which case the number of rows written may exceed it. The number of
+    /// edges of the foreign keys is only known once they are written.
+    #[must_use]
+    pub fn expected_rows(&self) -> Option<usize> {
+        self.expected_rows
+    }
+}
+
+/// Trait for types observing the progress of an export.
+///
+/// It is implemented for any closure taking a reference to a [`Progress`].
+///
+/// # Example
+///
+/// ```rust
+/// use sql2kg::{options::ExportOptions, progress::Progress};
+///
+/// let options = ExportOptions::default().with_progress_observer(|progress: &Progress| {
+///     eprintln!("{} {:?}: {}", progress.phase(), progress.class(), progress.rows());
+/// });
+/// assert!(options.progress_observer().is_some());
+/// ```
+pub trait ProgressObserver {
+    /// Called when a phase starts, after each batch of rows and after each
+    /// table or edge class is written.
+    ///
+    /// # Arguments
+    ///
+    /// * `progress` - The progress of the export.
+    fn on_progress(&self, progress: &Progress);
+}
+
+impl<F: Fn(&Progress)> ProgressObserver for F {
+    fn on_progress(&self, progress: &Progress) {
+        self(progress);
+    }
+}
+
+#[derive(Clone)]
+/// A shared handle to a [`ProgressObserver`], stored in the export options.
+pub(crate) struct SharedProgressObserver(pub(crate) Arc<dyn ProgressObserver + Send + Sync>);
+
+impl std::fmt::Debug for SharedProgressObserver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SharedProgressObserver")
+    }
+}
@@ -0,0 +1,185 @@
+//! Submodule defining the values of the properties which can be attached to
+//! the elements of the knowledge graph.
+
+use std::{fmt::Display, num::NonZeroU32};
+
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::{Pg, PgValue},
+};
+use diesel_dynamic_schema::dynamic_value::Any;
+
+use crate::primary_key::{
+    BIGINT_OID, BOOL_OID, BPCHAR_OID, DATE_OID, FLOAT4_OID, FLOAT8_OID, INTEGER_OID, SMALLINT_OID,
+    TEXT_OID, TIMESTAMP_OID, TIMESTAMPTZ_OID, UUID_OID, UnsupportedOid, VARCHAR_OID,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// An enum representing the typed value of a property, as read from a column
+/// of the database.
+pub enum PropertyValue {
+    /// A null value.
+    Null,
+    /// A boolean value.
+    Boolean(bool),
+    /// An integer (16-bit) value.
+    I16(i16),
+    /// An integer (32-bit) value.
+    I32(i32),
+    /// An integer (64-bit) value.
+    I64(i64),
+    /// A floating point (32-bit) value.
+    F32(f32),
+    /// A floating point (64-bit) value.
+    F64(f64),
+    /// A string value.
+    String(String),
+    /// A UUID value.
+    UUID(uuid::Uuid),
+    /// A timestamp without time zone.
+    Timestamp(chrono::NaiveDateTime),
+    /// A timestamp with time zone, normalized to UTC.
+    TimestampTz(chrono::DateTime<chrono::Utc>),
+    /// A date value.
+    Date(chrono::NaiveDate),
+}
+
+impl PropertyValue {
+    /// Returns the name of the SQL data type of the value.
+    #[must_use]
+    pub fn data_type(&self) -> &'static str {
+        match self {
+            PropertyValue::Null => "null",
+            PropertyValue::Boolean(_) => "boolean",
+            PropertyValue::I16(_) => "smallint",
+            PropertyValue::I32(_) => "integer",
+            PropertyValue::I64(_) => "bigint",
+            PropertyValue::F32(_) => "real",
+            PropertyValue::F64(_) => "double precision",
+            PropertyValue::String(_) => "text",
+            PropertyValue::UUID(_) => "uuid",
+            PropertyValue::Timestamp(_) => "timestamp",
+            PropertyValue::TimestampTz(_) => "timestamptz",
+            PropertyValue::Date(_) => "date",
+        }
+    }
+
+    /// Returns the OID of the type the value was read from, if not null.
+    pub(crate) fn oid(&self) -> Option<NonZeroU32> {
+        match self {
+            PropertyValue::Null => None,
+            PropertyValue::Boolean(_) => Some(BOOL_OID),
+            PropertyValue::I16(_) => Some(SMALLINT_OID),
+            PropertyValue::I32(_) => Some(INTEGER_OID),
+            PropertyValue::I64(_) => Some(BIGINT_OID),
+            PropertyValue::F32(_) => Some(FLOAT4_OID),
+            PropertyValue::F64(_) => Some(FLOAT8_OID),
+            PropertyValue::String(_) => Some(TEXT_OID),
+            PropertyValue::UUID(_) => Some(UUID_OID),
+            PropertyValue::Timestamp(_) => Some(TIMESTAMP_OID),
+            PropertyValue::TimestampTz(_) => Some(TIMESTAMPTZ_OID),
+            PropertyValue::Date(_) => Some(DATE_OID),
+        }
+    }
+
+    /// Returns whether the value is null.
+    #[must_use]
+    pub fn is_null(&self) -> bool {
+        matches!(self, PropertyValue::Null)
+    }
+}
+
+impl FromSql<Any, Pg> for PropertyValue {
+    fn from_sql(value: PgValue) -> deserialize::Result<Self> {
+        match value.get_oid() {
+            BOOL_OID => {
+                <bool as FromSql<diesel::sql_types::Bool, Pg>>::from_sql(value)
+                    .map(PropertyValue::Boolean)
+            }
+            SMALLINT_OID => {
+                <i16 as FromSql<diesel::sql_types::SmallInt, Pg>>::from_sql(value)
+                    .map(PropertyValue::I16)
+            }
+            INTEGER_OID => {
+                <i32 as FromSql<diesel::sql_types::Integer, Pg>>::from_sql(value)
+                    .map(PropertyValue::I32)
+            }
+            BIGINT_OID => {
+                <i64 as FromSql<diesel::sql_types::BigInt, Pg>>::from_sql(value)
+                    .map(PropertyValue::I64)
+            }
+            FLOAT4_OID => {
+                <f32 as FromSql<diesel::sql_types::Float, Pg>>::from_sql(value)
+                    .map(PropertyValue::F32)
+            }
+            FLOAT8_OID => {
+                <f64 as FromSql<diesel::sql_types::Double, Pg>>::from_sql(value)
+                    .map(PropertyValue::F64)
+            }
+            VARCHAR_OID | TEXT_OID | BPCHAR_OID => {
+                <String as FromSql<diesel::sql_types::Text, Pg>>::from_sql(value)
+                    .map(PropertyValue::String)
+            }
+            UUID_OID => {
+                <uuid::Uuid as FromSql<diesel::sql_types::Uuid, Pg>>::from_sql(value)
+                    .map(PropertyValue::UUID)
+            }
+            TIMESTAMP_OID => {
+                <chrono::NaiveDateTime as FromSql<diesel::sql_types::Timestamp, Pg>>::from_sql(
+                    value,
+                )
+                .map(PropertyValue::Timestamp)
+            }
+            TIMESTAMPTZ_OID => <chrono::DateTime<chrono::Utc> as FromSql<
+                diesel::sql_types::Timestamptz,
+                Pg,
+            >>::from_sql(value)
+            .map(PropertyValue::TimestampTz),
+            DATE_OID => {
+                <chrono::NaiveDate as FromSql<diesel::sql_types::Date, Pg>>::from_sql(value)
+                    .map(PropertyValue::Date)
+            }
+            oid => Err(Box::new(UnsupportedOid(oid))),
+        }
+    }
+
+    fn from_nullable_sql(value: Option<PgValue>) -> deserialize::Result<Self> {
+        match value {
+            Some(value) => Self::from_sql(value),
+            None => Ok(PropertyValue::Null),
+        }
+    }
+}
+
+impl TryFrom<PropertyValue> for crate::primary_key::PrimaryKey {
+    type Error = PropertyValue;
+
+    fn try_from(value: PropertyValue) -> Result<Self, Self::Error> {
+        match value {
+            PropertyValue::String(s) => Ok(s.into()),
+            PropertyValue::I32(i) => Ok(i.into()),
+            PropertyValue::I64(i) => Ok(i.into()),
+            PropertyValue::UUID(u) => Ok(u.into()),
+            other => Err(other),
+        }
+    }
+}
+
+impl Display for PropertyValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PropertyValue::Null => Ok(()),
+            PropertyValue::Boolean(b) => write!(f, "{b}"),
+            PropertyValue::I16(i) => write!(f, "{i}"),
+            PropertyValue::I32(i) => write!(f, "{i}"),
+            PropertyValue::I64(i) => write!(f, "{i}"),
+            PropertyValue::F32(x) => write!(f, "{x}"),
+            PropertyValue::F64(x) => write!(f, "{x}"),
+            PropertyValue::String(s) => write!(f, "{s}"),
+            PropertyValue::UUID(u) => write!(f, "{u}"),
+            PropertyValue::Timestamp(t) => write!(f, "{}", t.format("%Y-%m-%dT%H:%M:%S%.f")),
+            PropertyValue::TimestampTz(t) => write!(f, "{}", t.to_rfc3339()),
+            PropertyValue::Date(d) => write!(f, "{d}"),
+        }
+    }
+}
@@ -0,0 +1,858 @@
+//! Submodule providing a reader loading an exported knowledge graph back
+//! into memory.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+    path::Path,
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    csv::{CsvField, CsvWriter, parse_records, write_node_row},
+    delta::ExportedDelta,
+    manifest::Manifest,
+    node_name::NodeName,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A node read from `nodes.csv`.
+pub struct ExportedNode {
+    /// The parsed name of the node.
+    name: NodeName,
+    /// The identifiers of the node classes of the node.
+    node_class_ids: Vec<usize>,
+}
+
+impl ExportedNode {
+    /// Create a new `ExportedNode` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The parsed name of the node.
+    /// * `node_class_ids` - The identifiers of the node classes of the node.
+    pub(crate) fn new(name: NodeName, node_class_ids: Vec<usize>) -> Self {
+        Self { name, node_class_ids }
+    }
+
+    /// Returns the parsed name of the node.
+    #[must_use]
+    pub fn name(&self) -> &NodeName {
+        &self.name
+    }
+
+    /// Returns the identifiers of the node classes of the node, the first
+    /// one being the class of its own table.
+    #[must_use]
+    pub fn node_class_ids(&self) -> &[usize] {
+        &self.node_class_ids
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An edge class read from `edge_classes.csv`.
+pub struct ExportedEdgeClass {
+    /// The name of the edge class.
+    name: String,
+    /// The identifier of the node class of the sources.
+    src_node_class_id: usize,
+    /// The identifier of the node class of the destinations.
+    dst_node_class_id: usize,
+}
+
+impl ExportedEdgeClass {
+    /// Create a new `ExportedEdgeClass` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the edge class.
+    /// * `src_node_class_id` - The identifier of the node class of the sources.
+    /// * `dst_node_class_id` - The identifier of the node class of the
+    ///   destinations.
+    #[cfg(test)]
+    pub(crate) fn new(name: String, src_node_class_id: usize, dst_node_class_id: usize) -> Self {
+        Self { name, src_node_class_id, dst_node_class_id }
+    }
+
+    /// Returns the name of the edge class.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the identifier of the node class of the sources.
+    #[must_use]
+    pub fn src_node_class_id(&self) -> usize {
+        self.src_node_class_id
+    }
+
+    /// Returns the identifier of the node class of the destinations.
+    #[must_use]
+    pub fn dst_node_class_id(&self) -> usize {
+        self.dst_node_class_id
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// An edge read from `edges.csv`.
+pub struct ExportedEdge {
+    /// The identifier of the source node.
+    src_id: usize,
+    /// The identifier of the destination node.
+    dst_id: usize,
+    /// The identifier of the edge class.
+    edge_class_id: usize,
+}
+
+impl ExportedEdge {
+    /// Create a new `ExportedEdge` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `src_id` - The identifier of the source node.
+    /// * `dst_id` - The identifier of the destination node.
+    /// * `edge_class_id` - The identifier of the edge class.
+    #[cfg(test)]
+    pub(crate) fn new(src_id: usize, dst_id: usize, edge_class_id: usize) -> Self {
+        Self { src_id, dst_id, edge_class_id }
+    }
+
+    /// Returns the identifier of the source node.
+    #[must_use]
+    pub fn src_id(&self) -> usize {
+        self.src_id
+    }
+
+    /// Returns the identifier of the destination node.
+    #[must_use]
+    pub fn dst_id(&self) -> usize {
+        self.dst_id
+    }
+
+    /// Returns the identifier of the edge class.
+    #[must_use]
+    pub fn edge_class_id(&self) -> usize {
+        self.edge_class_id
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A knowledge graph loaded from an export directory.
+///
+/// # Implementative details
+///
+/// The reader loads `node_classes.csv`, `nodes.csv`, `edge_classes.csv` and
+/// `edges.csv`, checking their headers, parsing the node names, checking
+/// that they are unique and that every identifier refers to an existing row.
+/// When the directory contains a `manifest.json`, the number of rows and the
+/// SHA-256 digest of each file are also checked against it.
+///
+/// The schema meta graph written by `write_schema_kg_csvs` does not use node
+/// names and therefore cannot be read back.
+pub struct ExportedGraph {
+    /// The names of the node classes.
+    node_classes: Vec<String>,
+    /// The nodes.
+    nodes: Vec<ExportedNode>,
+    /// The edge classes.
+    edge_classes: Vec<ExportedEdgeClass>,
+    /// The edges.
+    edges: Vec<ExportedEdge>,
+    /// The manifest of the export, if any.
+    manifest: Option<Manifest>,
+}
+
+impl ExportedGraph {
+    /// Create a new `ExportedGraph` instance without a manifest.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_classes` - The names of the node classes.
+    /// * `nodes` - The nodes.
+    /// * `edge_classes` - The edge classes.
+    /// * `edges` - The edges.
+    #[cfg(test)]
+    pub(crate) fn new(
+        node_classes: Vec<String>,
+        nodes: Vec<ExportedNode>,
+        edge_classes: Vec<ExportedEdgeClass>,
+        edges: Vec<ExportedEdge>,
+    ) -> Self {
+        Self { node_classes, nodes, edge_classes, edges, manifest: None }
+    }
+
+    /// Reads the knowledge graph exported to the provided directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The export directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read, if it is malformed, if a
+    /// node name is repeated, or if it does not match the manifest.
+    pub fn read(directory: &Path) -> Result<Self, crate::errors::Error> {
+        let manifest = if directory.join(crate::manifest::MANIFEST_FILE_NAME).exists() {
+            Some(Manifest::read(directory)?)
+        } else {
+            None
+        };
+        if manifest.as_ref().is_some_and(|manifest| manifest.base_exported_at().is_some()) {
+            return Err(crate::errors::Error::InvalidExport(format!(
+                "{} is a delta, not a full export",
+                directory.display()
+            )));
+        }
+
+        let node_classes =
+            read_file(directory, "node_classes.csv", &["node_class"], manifest.as_ref())?
+                .into_iter()
+                .map(|mut record| record.remove(0))
+                .collect::<Vec<String>>();
+
+        let nodes =
+            read_file(directory, "nodes.csv", &["node", "node_class_ids"], manifest.as_ref())?
+                .into_iter()
+                .enumerate()
+                .map(|(row, record)| {
+                    let name = record[0].parse::<NodeName>()?;
+                    let node_class_ids = parse_node_class_ids(&record[1], node_classes.len(), row)?;
+                    Ok(ExportedNode { name, node_class_ids })
+                })
+                .collect::<Result<Vec<ExportedNode>, crate::errors::Error>>()?;
+        // The deltas refer to the nodes by name, hence the names must be
+        // unique.
+        let mut names = HashSet::with_capacity(nodes.len());
+        if let Some(row) = nodes.iter().position(|node| !names.insert(&node.name)) {
+            return Err(crate::errors::Error::InvalidExport(format!(
+                "nodes.csv: row {row} repeats the node `{}`",
+                nodes[row].name
+            )));
+        }
+
+        let edge_classes = read_file(
+            directory,
+            "edge_classes.csv",
+            &["edge_class", "src_node_class_id", "dst_node_class_id"],
+            manifest.as_ref(),
+        )?
+        .into_iter()
+        .enumerate()
+        .map(|(row, mut record)| {
+            let file = "edge_classes.csv";
+            let src_node_class_id =
+                parse_id(&record[1], node_classes.len(), file, row, "node class")?;
+            let dst_node_class_id =
+                parse_id(&record[2], node_classes.len(), file, row, "node class")?;
+            Ok(ExportedEdgeClass { name: record.remove(0), src_node_class_id, dst_node_class_id })
+        })
+        .collect::<Result<Vec<ExportedEdgeClass>, crate::errors::Error>>()?;
+
+        let edges = read_file(
+            directory,
+            "edges.csv",
+            &["src_id", "dst_id", "edge_class_id"],
+            manifest.as_ref(),
+        )?
+        .into_iter()
+        .enumerate()
+        .map(|(row, record)| {
+            let file = "edges.csv";
+            Ok(ExportedEdge {
+                src_id: parse_id(&record[0], nodes.len(), file, row, "node")?,
+                dst_id: parse_id(&record[1], nodes.len(), file, row, "node")?,
+                edge_class_id: parse_id(&record[2], edge_classes.len(), file, row, "edge class")?,
+            })
+        })
+        .collect::<Result<Vec<ExportedEdge>, crate::errors::Error>>()?;
+
+        Ok(Self { node_classes, nodes, edge_classes, edges, manifest })
+    }
+
+    /// Returns the names of the node classes, indexed by identifier.
+    #[must_use]
+    pub fn node_classes(&self) -> &[String] {
+        &self.node_classes
+    }
+
+    /// Returns the nodes, indexed by identifier.
+    #[must_use]
+    pub fn nodes(&self) -> &[ExportedNode] {
+        &self.nodes
+    }
+
+    /// Returns the edge classes, indexed by identifier.
+    #[must_use]
+    pub fn edge_classes(&self) -> &[ExportedEdgeClass] {
+        &self.edge_classes
+    }
+
+    /// Returns the edges, in the order of `edges.csv`.
+    #[must_use]
+    pub fn edges(&self) -> &[ExportedEdge] {
+        &self.edges
+    }
+
+    /// Returns the differences from this graph to the provided, more recent
+    /// one.
+    ///
+    /// # Arguments
+    ///
+    /// * `new` - The graph of the more recent export.
+    #[must_use]
+    pub fn diff(&self, new: &ExportedGraph) -> crate::diff::GraphDiff {
+        crate::diff::GraphDiff::between(self, new)
+    }
+
+    /// Applies the provided delta, produced by an incremental export
+    /// following the export of this graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - The delta to apply.
+    ///
+    /// # Implementative details
+    ///
+    /// The nodes of the delta which are not in the graph are appended to the
+    /// nodes, while the node classes of the others are updated. The outgoing
+    /// edges of the recomputed edge classes of the nodes of the delta are
+    /// then replaced by the edges of the delta. The identifiers of the
+    /// existing nodes are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the graph has no manifest, if the delta does not
+    /// follow the export of the graph, or if the delta references node
+    /// classes, edge classes or nodes which are not in the graph.
+    pub fn apply_delta(&mut self, delta: &ExportedDelta) -> Result<(), crate::errors::Error> {
+        let invalid = |reason: String| crate::errors::Error::InvalidExport(reason);
+        let manifest = self
+            .manifest
+            .as_ref()
+            .ok_or_else(|| invalid("the graph has no manifest".to_owned()))?;
+        if delta.manifest().base_exported_at() != Some(manifest.exported_at()) {
+            return Err(invalid(format!(
+                "the delta applies to the export started at {:?}, not at {}",
+                delta.manifest().base_exported_at(),
+                manifest.exported_at()
+            )));
+        }
+        if delta.manifest().schema_fingerprint() != manifest.schema_fingerprint() {
+            return Err(invalid("the delta was exported from a different schema".to_owned()));
+        }
+
+        let mut node_ids: HashMap<NodeName, usize> =
+            self.nodes.iter().enumerate().map(|(id, node)| (node.name.clone(), id)).collect();
+        let mut changed_node_ids = HashSet::with_capacity(delta.nodes().len());
+        for node in delta.nodes() {
+            if let Some(id) = node.node_class_ids.iter().find(|id| **id >= self.node_classes.len())
+            {
+                return Err(invalid(format!("the delta references the unknown node class `{id}`")));
+            }
+            let id = *node_ids.entry(node.name.clone()).or_insert(self.nodes.len());
+            if id == self.nodes.len() {
+                self.nodes.push(node.clone());
+            } else {
+                self.nodes[id].node_class_ids.clone_from(&node.node_class_ids);
+            }
+            changed_node_ids.insert(id);
+        }
+
+        let edge_class_ids: HashMap<&str, usize> = self
+            .edge_classes
+            .iter()
+            .enumerate()
+            .map(|(id, edge_class)| (edge_class.name.as_str(), id))
+            .collect();
+        let edge_class_id = |name: &str| {
+            edge_class_ids.get(name).copied().ok_or_else(|| {
+                invalid(format!("the delta references the unknown edge class `{name}`"))
+            })
+        };
+        let recomputed_edge_class_ids = delta
+            .edge_classes()
+            .iter()
+            .map(|name| edge_class_id(name))
+            .collect::<Result<HashSet<usize>, crate::errors::Error>>()?;
+        self.edges.retain(|edge| {
+            !changed_node_ids.contains(&edge.src_id)
+                || !recomputed_edge_class_ids.contains(&edge.edge_class_id)
+        });
+        for (src, edge_class, dst) in delta.edges() {
+            let node_id = |name: &NodeName| {
+                node_ids
+                    .get(name)
+                    .copied()
+                    .ok_or_else(|| crate::errors::Error::NodeNotFound(name.to_string()))
+            };
+            self.edges.push(ExportedEdge {
+                src_id: node_id(src)?,
+                dst_id: node_id(dst)?,
+                edge_class_id: edge_class_id(edge_class)?,
+            });
+        }
+
+        self.manifest =
+            Some(manifest.after_delta(delta.manifest(), self.nodes.len(), self.edges.len()));
+        Ok(())
+    }
+
+    /// Writes the graph to the provided directory, in the format of a full
+    /// export, together with its manifest and grape loader configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory where to write the graph.
+    ///
+    /// # Implementative details
+    ///
+    /// Only the files read back are written, hence `edge_properties.csv` is
+    /// not carried over.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the graph has no manifest or if writing to the
+    /// files fails.
+    pub fn write(&mut self, directory: &Path) -> Result<(), crate::errors::Error> {
+        let manifest = self.manifest.as_ref().ok_or_else(|| {
+            crate::errors::Error::InvalidExport("the graph has no manifest".to_owned())
+        })?;
+        if !directory.exists() {
+            std::fs::create_dir_all(directory)?;
+        }
+
+        let mut files = Vec::with_capacity(4);
+        let mut writer = CsvWriter::create(directory, "node_classes.csv")?;
+        writeln!(writer, "node_class")?;
+        for node_class in &self.node_classes {
+            writeln!(writer, "{}", CsvField(node_class))?;
+        }
+        files.push(writer.finish()?);
+
+        let mut writer = CsvWriter::create(directory, "nodes.csv")?;
+        writeln!(writer, "node,node_class_ids")?;
+        for node in &self.nodes {
+            write_node_row(&mut writer, &node.name, &node.node_class_ids)?;
+        }
+        files.push(writer.finish()?);
+
+        let mut writer = CsvWriter::create(directory, "edge_classes.csv")?;
+        writeln!(writer, "edge_class,src_node_class_id,dst_node_class_id")?;
+        for edge_class in &self.edge_classes {
+            writeln!(
+                writer,
+                "{},{},{}",
+                CsvField(&edge_class.name),
+                edge_class.src_node_class_id,
+                edge_class.dst_node_class_id
+            )?;
+        }
+        files.push(writer.finish()?);
+
+        let mut writer = CsvWriter::create(directory, "edges.csv")?;
+        writeln!(writer, "src_id,dst_id,edge_class_id")?;
+        for edge in &self.edges {
+            writeln!(writer, "{},{},{}", edge.src_id, edge.dst_id, edge.edge_class_id)?;
+        }
+        files.push(writer.finish()?);
+
+        let manifest = Manifest::new(
+            manifest.exported_at(),
+            manifest.schema_fingerprint().to_owned(),
+            files,
+            manifest.options().clone(),
+        );
+        manifest.write(directory)?;
+        manifest.grape_config(directory)?.write(directory)?;
+        self.manifest = Some(manifest);
+        Ok(())
+    }
+
+    /// Returns the manifest of the export, if the directory contained one.
+    #[must_use]
+    pub fn manifest(&self) -> Option<&Manifest> {
+        self.manifest.as_ref()
+    }
+}
+
+/// Reads the records of the provided file, excluding the header, after
+/// checking its header, the number of fields of each record and, if a
+/// manifest is provided, its number of rows and digest.
+///
+/// # Arguments
+///
+/// * `directory` - The export directory.
+/// * `file_name` - The name of the file.
+/// * `header` - The expected header of the file.
+/// * `manifest` - The manifest of the export, if any.
+pub(crate) fn read_file(
+    directory: &Path,
+    file_name: &str,
+    header: &[&str],
+    manifest: Option<&Manifest>,
+) -> Result<Vec<Vec<String>>, crate::errors::Error> {
+    let invalid =
+        |reason: String| crate::errors::Error::InvalidExport(format!("{file_name}: {reason}"));
+    let content = std::fs::read_to_string(directory.join(file_name))?;
+    let mut records = parse_records(&content).map_err(invalid)?;
+    if records.is_empty() || records[0] != header {
+        return Err(invalid(format!("expected header `{}`", header.join(","))));
+    }
+    records.remove(0);
+    if let Some(row) = records.iter().position(|record| record.len() != header.len()) {
+        return Err(invalid(format!("row {row} does not have {} fields", header.len())));
+    }
+    if let Some(manifest) = manifest {
+        let entry = manifest
+            .file(file_name)
+            .ok_or_else(|| invalid("missing from the manifest".to_owned()))?;
+        if entry.rows() != records.len() {
+            return Err(invalid(format!(
+                "the manifest lists {} rows, found {}",
+                entry.rows(),
+                records.len()
+            )));
+        }
+        if entry.sha256() != format!("{:x}", Sha256::digest(content.as_bytes())) {
+            return Err(invalid("the digest does not match the manifest".to_owned()));
+        }
+    }
+    Ok(records)
+}
+
+/// Parses the `|`-separated node class identifiers of a row of `nodes.csv`.
+///
+/// # Arguments
+///
+/// * `value` - The textual identifiers.
+/// * `number_of_node_classes` - The number of node classes.
+/// * `row` - The row of `nodes.csv` containing the identifiers.
+pub(crate) fn parse_node_class_ids(
+    value: &str,
+    number_of_node_classes: usize,
+    row: usize,
+) -> Result<Vec<usize>, crate::errors::Error> {
+    value
+        .split('|')
+        .map(|id| parse_id(id, number_of_node_classes, "nodes.csv", row, "node class"))
+        .collect()
+}
+
+/// Parses an identifier, checking that it refers to one of the provided
+/// number of rows.
+///
+/// # Arguments
+///
+/// * `value` - The textual identifier.
+/// * `number_of_rows` - The number of rows the identifier may refer to.
+/// * `file_name` - The name of the file containing the identifier.
+/// * `row` - The row of the file containing the identifier.
+/// * `kind` - What the identifier refers to.
+fn parse_id(
+    value: &str,
+    number_of_rows: usize,
+    file_name: &str,
+    row: usize,
+    kind: &str,
+) -> Result<usize, crate::errors::Error> {
+    value.parse::<usize>().ok().filter(|id| *id < number_of_rows).ok_or_else(|| {
+        crate::errors::Error::InvalidExport(format!(
+            "{file_name}: row {row} references the unknown {kind} `{value}`"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The name of the edge class of the test exports.
+    const AUTHOR: &str = "posts(author_id) -> users(id)";
+
+    /// Returns an empty temporary directory for the provided test.
+    fn test_directory(test_name: &str) -> std::path::PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("sql2kg-reader-{}-{test_name}", std::process::id()));
+        if directory.exists() {
+            std::fs::remove_dir_all(&directory).unwrap();
+        }
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    /// Writes the provided files to the provided directory, together with
+    /// their manifest, which is the one of a delta if `base_exported_at` is
+    /// provided.
+    fn write_export(
+        directory: &Path,
+        files: &[(&str, &str)],
+        exported_at: u64,
+        base_exported_at: Option<u64>,
+    ) {
+        std::fs::create_dir_all(directory).unwrap();
+        let files = files
+            .iter()
+            .map(|(file_name, content)| {
+                let mut writer = CsvWriter::create(directory, file_name).unwrap();
+                writer.write_all(content.as_bytes()).unwrap();
+                writer.finish().unwrap()
+            })
+            .collect();
+        let manifest =
+            Manifest::new(exported_at, "fingerprint".to_owned(), files, serde_json::json!({}));
+        let manifest = match base_exported_at {
+            Some(base_exported_at) => manifest.with_base_exported_at(base_exported_at),
+            None => manifest,
+        };
+        manifest.write(directory).unwrap();
+    }
+
+    /// Writes a full export with the provided nodes and edges, whose edges
+    /// all belong to the single [`AUTHOR`] edge class.
+    fn write_full_export(directory: &Path, nodes: &str, edges: &str, exported_at: u64) {
+        write_export(
+            directory,
+            &[
+                ("node_classes.csv", "node_class\n\"users\"\n\"posts\"\n"),
+                ("nodes.csv", &format!("node,node_class_ids\n{nodes}")),
+                (
+                    "edge_classes.csv",
+                    &format!("edge_class,src_node_class_id,dst_node_class_id\n\"{AUTHOR}\",1,0\n"),
+                ),
+                ("edges.csv", &format!("src_id,dst_id,edge_class_id\n{edges}")),
+            ],
+            exported_at,
+            None,
+        );
+    }
+
+    /// Returns the names of the nodes of the provided graph.
+    fn node_names(graph: &ExportedGraph) -> Vec<String> {
+        graph.nodes().iter().map(|node| node.name().to_string()).collect()
+    }
+
+    /// Asserts that reading the provided directory fails with an
+    /// [`InvalidExport`](crate::errors::Error::InvalidExport) error whose
+    /// message contains the provided text.
+    fn assert_invalid(directory: &Path, expected: &str) {
+        match ExportedGraph::read(directory) {
+            Err(crate::errors::Error::InvalidExport(reason)) => {
+                assert!(reason.contains(expected), "unexpected reason `{reason}`");
+            }
+            other => panic!("expected an invalid export, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_read_round_trip() {
+        let directory = test_directory("round-trip");
+        let original = directory.join("original");
+        write_full_export(
+            &original,
+            "\"users(1)\",0\n\"users('a,\nb')\",0\n\"posts(1, 2::bigint)\",1|0\n",
+            "2,0,0\n2,1,0\n",
+            100,
+        );
+
+        let mut graph = ExportedGraph::read(&original).unwrap();
+        assert_eq!(graph.node_classes(), ["users".to_owned(), "posts".to_owned()]);
+        assert_eq!(
+            node_names(&graph),
+            ["users(1)".to_owned(), "users('a,\nb')".to_owned(), "posts(1, 2::bigint)".to_owned()]
+        );
+        assert_eq!(graph.nodes()[2].node_class_ids(), [1, 0]);
+        assert_eq!(graph.edge_classes().len(), 1);
+        assert_eq!(graph.edge_classes()[0].name(), AUTHOR);
+        assert_eq!(graph.edge_classes()[0].src_node_class_id(), 1);
+        assert_eq!(graph.edge_classes()[0].dst_node_class_id(), 0);
+        assert_eq!(
+            graph
+                .edges()
+                .iter()
+                .map(|edge| (edge.src_id(), edge.dst_id(), edge.edge_class_id()))
+                .collect::<Vec<_>>(),
+            [(2, 0, 0), (2, 1, 0)]
+        );
+
+        let copy = directory.join("copy");
+        graph.write(&copy).unwrap();
+        let copied = ExportedGraph::read(&copy).unwrap();
+        assert_eq!(copied, graph);
+        for file_name in ["node_classes.csv", "nodes.csv", "edge_classes.csv", "edges.csv"] {
+            assert_eq!(
+                std::fs::read(copy.join(file_name)).unwrap(),
+                std::fs::read(original.join(file_name)).unwrap()
+            );
+        }
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_read_rejects_bad_header() {
+        let directory = test_directory("bad-header");
+        write_export(
+            &directory,
+            &[
+                ("node_classes.csv", "node_class\n\"users\"\n\"posts\"\n"),
+                ("nodes.csv", "name,node_class_ids\n\"users(1)\",0\n"),
+                (
+                    "edge_classes.csv",
+                    &format!("edge_class,src_node_class_id,dst_node_class_id\n\"{AUTHOR}\",1,0\n"),
+                ),
+                ("edges.csv", "src_id,dst_id,edge_class_id\n"),
+            ],
+            100,
+            None,
+        );
+        assert_invalid(&directory, "nodes.csv: expected header `node,node_class_ids`");
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_read_rejects_wrong_field_count() {
+        let directory = test_directory("field-count");
+        write_full_export(&directory, "\"users(1)\",0\n\"posts(1)\",1\n", "1,0\n", 100);
+        assert_invalid(&directory, "edges.csv: row 0 does not have 3 fields");
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_read_rejects_unknown_identifiers() {
+        let directory = test_directory("unknown-ids");
+        write_full_export(&directory, "\"users(1)\",0\n\"posts(1)\",1\n", "1,2,0\n", 100);
+        assert_invalid(&directory, "edges.csv: row 0 references the unknown node `2`");
+
+        write_full_export(&directory, "\"users(1)\",0\n\"posts(1)\",1\n", "1,0,1\n", 100);
+        assert_invalid(&directory, "edges.csv: row 0 references the unknown edge class `1`");
+
+        write_full_export(&directory, "\"users(1)\",2\n", "", 100);
+        assert_invalid(&directory, "nodes.csv: row 0 references the unknown node class `2`");
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_read_rejects_duplicate_nodes() {
+        let directory = test_directory("duplicate-nodes");
+        write_full_export(&directory, "\"users(1)\",0\n\"users(2)\",0\n\"users(1)\",0\n", "", 100);
+        assert_invalid(&directory, "nodes.csv: row 2 repeats the node `users(1)`");
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_read_rejects_manifest_mismatches() {
+        let directory = test_directory("manifest");
+        write_full_export(&directory, "\"users(1)\",0\n\"posts(1)\",1\n", "1,0,0\n", 100);
+        let edges = std::fs::read_to_string(directory.join("edges.csv")).unwrap();
+
+        // A row is appended after the manifest was written.
+        std::fs::write(directory.join("edges.csv"), format!("{edges}1,0,0\n")).unwrap();
+        assert_invalid(&directory, "edges.csv: the manifest lists 1 rows, found 2");
+
+        // The number of rows is unchanged, but the content differs.
+        std::fs::write(directory.join("edges.csv"), edges.replace("1,0,0", "0,1,0")).unwrap();
+        assert_invalid(&directory, "edges.csv: the digest does not match the manifest");
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_delta_round_trip() {
+        let directory = test_directory("delta");
+        let full = directory.join("full");
+        write_full_export(
+            &full,
+            "\"users(1)\",0\n\"users(2)\",0\n\"posts(1)\",1\n",
+            "2,0,0\n",
+            100,
+        );
+        // The first post moves to the second user, and a second post by the
+        // first user is added.
+        let delta = directory.join("delta");
+        write_export(
+            &delta,
+            &[
+                ("nodes.csv", "node,node_class_ids\n\"posts(1)\",1\n\"posts(2)\",1\n"),
+                ("edge_classes.csv", &format!("edge_class\n\"{AUTHOR}\"\n")),
+                (
+                    "edges.csv",
+                    &format!(
+                        "src,dst,edge_class\n\"posts(1)\",\"users(2)\",\"{AUTHOR}\"\n\
+                         \"posts(2)\",\"users(1)\",\"{AUTHOR}\"\n"
+                    ),
+                ),
+            ],
+            200,
+            Some(100),
+        );
+        let expected = directory.join("expected");
+        write_full_export(
+            &expected,
+            "\"users(1)\",0\n\"users(2)\",0\n\"posts(1)\",1\n\"posts(2)\",1\n",
+            "2,1,0\n3,0,0\n",
+            200,
+        );
+
+        let exported_delta = ExportedDelta::read(&delta).unwrap();
+        assert_eq!(exported_delta.nodes().len(), 2);
+        assert_eq!(exported_delta.edge_classes(), [AUTHOR.to_owned()]);
+        assert_eq!(exported_delta.edges().len(), 2);
+        assert_eq!(exported_delta.manifest().base_exported_at(), Some(100));
+
+        let mut graph = ExportedGraph::read(&full).unwrap();
+        graph.apply_delta(&exported_delta).unwrap();
+        let expected = ExportedGraph::read(&expected).unwrap();
+        assert_eq!(node_names(&graph), node_names(&expected));
+        assert_eq!(graph.nodes(), expected.nodes());
+        assert_eq!(graph.edge_classes(), expected.edge_classes());
+        assert_eq!(graph.edges(), expected.edges());
+        let manifest = graph.manifest().unwrap();
+        assert_eq!(manifest.exported_at(), 200);
+        assert_eq!(manifest.number_of_nodes(), 4);
+        assert_eq!(manifest.number_of_edges(), 2);
+
+        // The updated graph is written and read back as a full export, to
+        // which the same delta no longer applies.
+        let updated = directory.join("updated");
+        graph.write(&updated).unwrap();
+        let mut written = ExportedGraph::read(&updated).unwrap();
+        assert_eq!(written.nodes(), expected.nodes());
+        assert_eq!(written.edges(), expected.edges());
+        assert!(written.apply_delta(&exported_delta).is_err());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_full_export_and_delta_are_told_apart() {
+        let directory = test_directory("kinds");
+        let full = directory.join("full");
+        write_full_export(&full, "\"users(1)\",0\n", "", 100);
+        let delta = directory.join("delta");
+        write_export(
+            &delta,
+            &[
+                ("nodes.csv", "node,node_class_ids\n"),
+                ("edge_classes.csv", "edge_class\n"),
+                ("edges.csv", "src,dst,edge_class\n"),
+            ],
+            200,
+            Some(100),
+        );
+
+        assert!(matches!(ExportedDelta::read(&full), Err(crate::errors::Error::InvalidExport(_))));
+        assert!(matches!(ExportedGraph::read(&delta), Err(crate::errors::Error::InvalidExport(_))));
+        // A delta must follow the export it is applied to.
+        let mut graph = ExportedGraph::read(&full).unwrap();
+        write_export(
+            &delta,
+            &[
+                ("nodes.csv", "node,node_class_ids\n"),
+                ("edge_classes.csv", "edge_class\n"),
+                ("edges.csv", "src,dst,edge_class\n"),
+            ],
+            300,
+            Some(200),
+        );
+        assert!(graph.apply_delta(&ExportedDelta::read(&delta).unwrap()).is_err());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,79 @@
+//! Submodule defining the report returned after exporting a knowledge graph.
+
+use std::collections::BTreeMap;
+
+use sql_traits::traits::DatabaseLike;
+use time_requirements::prelude::TimeTracker;
+
+use crate::{edge_class::EdgeClass, manifest::Manifest, statistics::ExportStatistics};
+
+/// A struct summarizing the outcome of a knowledge graph export.
+pub struct ExportReport<'db, DB: DatabaseLike> {
+    /// The time spent in the different phases of the export.
+    time_tracker: TimeTracker,
+    /// The number of dangling references encountered per edge class.
+    dangling_references: BTreeMap<EdgeClass<'db, DB>, usize>,
+    /// The manifest written in the export directory.
+    manifest: Manifest,
+    /// The per-class statistics of the export, if requested.
+    statistics: Option<ExportStatistics>,
+}
+
+impl<'db, DB: DatabaseLike> ExportReport<'db, DB> {
+    /// Create a new `ExportReport` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_tracker` - The time spent in the different phases of the export.
+    /// * `dangling_references` - The number of dangling references encountered
+    ///   per edge class.
+    /// * `manifest` - The manifest written in the export directory.
+    /// * `statistics` - The per-class statistics of the export, if requested.
+    pub(crate) fn new(
+        time_tracker: TimeTracker,
+        dangling_references: BTreeMap<EdgeClass<'db, DB>, usize>,
+        manifest: Manifest,
+        statistics: Option<ExportStatistics>,
+    ) -> Self {
+        Self { time_tracker, dangling_references, manifest, statistics }
+    }
+
+    /// Returns a reference to the time tracker of the export.
+    #[must_use]
+    pub fn time_tracker(&self) -> &TimeTracker {
+        &self.time_tracker
+    }
+
+    /// Consumes the report and returns the time tracker of the export.
+    #[must_use]
+    pub fn into_time_tracker(self) -> TimeTracker {
+        self.time_tracker
+    }
+
+    /// Returns the number of dangling references encountered per edge class.
+    ///
+    /// Edge classes without dangling references are not included.
+    #[must_use]
+    pub fn dangling_references(&self) -> &BTreeMap<EdgeClass<'db, DB>, usize> {
+        &self.dangling_references
+    }
+
+    /// Returns the total number of dangling references encountered.
+    #[must_use]
+    pub fn number_of_dangling_references(&self) -> usize {
+        self.dangling_references.values().sum()
+    }
+
+    /// Returns the manifest written in the export directory.
+    #[must_use]
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Returns the per-class statistics of the export, if requested with
+    /// [`ExportOptions::with_statistics`](crate::options::ExportOptions::with_statistics).
+    #[must_use]
+    pub fn statistics(&self) -> Option<&ExportStatistics> {
+        self.statistics.as_ref()
+    }
+}
@@ -0,0 +1,134 @@
+//! Submodule defining the node and edge classes of the schema-level meta
+//! graph, which describes the data model of the database rather than its
+//! data.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// An enum representing the node classes of the schema meta graph.
+pub enum SchemaNodeClass {
+    /// A table of the database.
+    Table,
+    /// A column of a table.
+    Column,
+    /// A foreign key constraint.
+    ForeignKey,
+    /// A table whose rows are nodes of the data graph, i.e. a table with a
+    /// primary key.
+    NodeClass,
+    /// A foreign key whose values are edges of the data graph.
+    EdgeClass,
+}
+
+impl SchemaNodeClass {
+    /// All of the schema node classes, sorted by identifier.
+    pub const ALL: [SchemaNodeClass; 5] = [
+        SchemaNodeClass::Table,
+        SchemaNodeClass::Column,
+        SchemaNodeClass::ForeignKey,
+        SchemaNodeClass::NodeClass,
+        SchemaNodeClass::EdgeClass,
+    ];
+
+    /// Returns the identifier of the node class in `node_classes.csv`.
+    #[must_use]
+    pub fn id(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the name of the node class.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            SchemaNodeClass::Table => "table",
+            SchemaNodeClass::Column => "column",
+            SchemaNodeClass::ForeignKey => "foreign_key",
+            SchemaNodeClass::NodeClass => "node_class",
+            SchemaNodeClass::EdgeClass => "edge_class",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// An enum representing the edge classes of the schema meta graph.
+pub enum SchemaEdgeClass {
+    /// From a table to one of its columns.
+    HasColumn,
+    /// From a table to one of its primary key columns.
+    HasPrimaryKeyColumn,
+    /// From a table to one of the tables it extends, directly or not.
+    Extends,
+    /// From a table to a table it references through a foreign key.
+    References,
+    /// From a foreign key to the table hosting it.
+    HostTable,
+    /// From a foreign key to the table it references.
+    ReferencedTable,
+    /// From a foreign key to one of its host columns.
+    HostColumn,
+    /// From a foreign key to one of the columns it references.
+    ReferencedColumn,
+}
+
+impl SchemaEdgeClass {
+    /// All of the schema edge classes, sorted by identifier.
+    pub const ALL: [SchemaEdgeClass; 8] = [
+        SchemaEdgeClass::HasColumn,
+        SchemaEdgeClass::HasPrimaryKeyColumn,
+        SchemaEdgeClass::Extends,
+        SchemaEdgeClass::References,
+        SchemaEdgeClass::HostTable,
+        SchemaEdgeClass::ReferencedTable,
+        SchemaEdgeClass::HostColumn,
+        SchemaEdgeClass::ReferencedColumn,
+    ];
+
+    /// Returns the identifier of the edge class in `edge_classes.csv`.
+    #[must_use]
+    pub fn id(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the name of the edge class.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            SchemaEdgeClass::HasColumn => "has_column",
+            SchemaEdgeClass::HasPrimaryKeyColumn => "has_primary_key_column",
+            SchemaEdgeClass::Extends => "extends",
+            SchemaEdgeClass::References => "references",
+            SchemaEdgeClass::HostTable => "host_table",
+            SchemaEdgeClass::ReferencedTable => "referenced_table",
+            SchemaEdgeClass::HostColumn => "host_column",
+            SchemaEdgeClass::ReferencedColumn => "referenced_column",
+        }
+    }
+
+    /// Returns the node class of the sources of the edges of this class.
+    #[must_use]
+    pub fn source(self) -> SchemaNodeClass {
+        match self {
+            SchemaEdgeClass::HasColumn
+            | SchemaEdgeClass::HasPrimaryKeyColumn
+            | SchemaEdgeClass::Extends
+            | SchemaEdgeClass::References => SchemaNodeClass::Table,
+            SchemaEdgeClass::HostTable
+            | SchemaEdgeClass::ReferencedTable
+            | SchemaEdgeClass::HostColumn
+            | SchemaEdgeClass::ReferencedColumn => SchemaNodeClass::ForeignKey,
+        }
+    }
+
+    /// Returns the node class of the destinations of the edges of this class.
+    #[must_use]
+    pub fn destination(self) -> SchemaNodeClass {
+        match self {
+            SchemaEdgeClass::HasColumn
+            | SchemaEdgeClass::HasPrimaryKeyColumn
+            | SchemaEdgeClass::HostColumn
+            | SchemaEdgeClass::ReferencedColumn => SchemaNodeClass::Column,
+            SchemaEdgeClass::Extends
+            | SchemaEdgeClass::References
+            | SchemaEdgeClass::HostTable
+            | SchemaEdgeClass::ReferencedTable => SchemaNodeClass::Table,
+        }
+    }
+}
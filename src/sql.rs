@@ -0,0 +1,367 @@
+//! Submodule providing helpers to build the raw SQL queries which cannot be
+//! expressed through the dynamic schema DSL.
+
+use sql_traits::traits::{ColumnLike, TableLike};
+
+/// Returns the provided identifier quoted as a PostgreSQL identifier.
+///
+/// # Arguments
+///
+/// * `identifier` - The identifier to quote.
+pub(crate) fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Returns the provided value quoted as a PostgreSQL string literal.
+///
+/// # Arguments
+///
+/// * `value` - The value to quote.
+pub(crate) fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Returns the quoted, schema-qualified name of the provided table.
+///
+/// # Arguments
+///
+/// * `table` - The table whose name should be returned.
+pub(crate) fn qualified_table_name<T: TableLike>(table: &T) -> String {
+    match table.table_schema() {
+        Some(schema) => {
+            format!("{}.{}", quote_identifier(schema), quote_identifier(table.table_name()))
+        }
+        None => quote_identifier(table.table_name()),
+    }
+}
+
+/// The query selecting the UNIX timestamp, in seconds, of the start of the
+/// oldest transaction running on the current database, and at most of the
+/// start of the current transaction.
+pub(crate) const EXPORT_TIMESTAMP_QUERY: &str = "SELECT floor(extract(epoch FROM LEAST(now(), \
+     (SELECT min(xact_start) FROM pg_stat_activity WHERE datname = current_database()))))::bigint \
+     AS \"exported_at\"";
+
+/// Returns a query selecting, for each row of the host table whose foreign key
+/// values are not null, its primary key, its foreign key values and the
+/// primary key of the row of the referenced table it points to through the
+/// provided foreign key columns.
+///
+/// # Arguments
+///
+/// * `host_table` - The table hosting the foreign key.
+/// * `host_primary_key_columns` - The primary key columns of the host table.
+/// * `host_columns` - The foreign key columns of the host table.
+/// * `referenced_table` - The table referenced by the foreign key.
+/// * `referenced_columns` - The columns referenced by the foreign key.
+/// * `referenced_primary_key_columns` - The primary key columns of the
+///   referenced table.
+/// * `condition` - An optional condition on the rows of the host table, which
+///   is aliased as `h`.
+///
+/// # Implementative details
+///
+/// The join is a left join, hence the rows whose foreign key values do not
+/// match any row of the referenced table are returned with a null primary
+/// key of the referenced row.
+pub(crate) fn referenced_primary_key_query<T: TableLike, C: ColumnLike>(
+    host_table: &T,
+    host_primary_key_columns: &[&C],
+    host_columns: &[&C],
+    referenced_table: &T,
+    referenced_columns: &[&C],
+    referenced_primary_key_columns: &[&C],
+    condition: Option<&str>,
+) -> String {
+    let selected =
+        host_primary_key_columns
+            .iter()
+            .enumerate()
+            .map(|(i, col)| format!("h.{} AS \"src_{i}\"", quote_identifier(col.column_name())))
+            .chain(
+                host_columns.iter().enumerate().map(|(i, col)| {
+                    format!("h.{} AS \"fk_{i}\"", quote_identifier(col.column_name()))
+                }),
+            )
+            .chain(referenced_primary_key_columns.iter().enumerate().map(|(i, col)| {
+                format!("r.{} AS \"dst_{i}\"", quote_identifier(col.column_name()))
+            }))
+            .collect::<Vec<String>>();
+    let join_conditions = host_columns
+        .iter()
+        .zip(referenced_columns)
+        .map(|(host_col, referenced_col)| {
+            format!(
+                "h.{} = r.{}",
+                quote_identifier(host_col.column_name()),
+                quote_identifier(referenced_col.column_name())
+            )
+        })
+        .collect::<Vec<String>>();
+    let conditions = host_columns
+        .iter()
+        .map(|col| format!("h.{} IS NOT NULL", quote_identifier(col.column_name())))
+        .chain(condition.map(str::to_owned))
+        .collect::<Vec<String>>();
+
+    format!(
+        "SELECT {} FROM {} AS h LEFT JOIN {} AS r ON {} WHERE {}",
+        selected.join(", "),
+        qualified_table_name(host_table),
+        qualified_table_name(referenced_table),
+        join_conditions.join(" AND "),
+        conditions.join(" AND ")
+    )
+}
+
+/// Returns a query selecting, for each row of the host table satisfying the
+/// provided condition and whose foreign key values are not null, its primary
+/// key followed by its foreign key values.
+///
+/// # Arguments
+///
+/// * `host_table` - The table hosting the foreign key.
+/// * `host_primary_key_columns` - The primary key columns of the host table.
+/// * `host_columns` - The foreign key columns of the host table.
+/// * `condition` - The condition on the rows of the host table, which is
+///   aliased as `h`.
+pub(crate) fn foreign_key_values_query<T: TableLike, C: ColumnLike>(
+    host_table: &T,
+    host_primary_key_columns: &[&C],
+    host_columns: &[&C],
+    condition: &str,
+) -> String {
+    let selected =
+        host_primary_key_columns
+            .iter()
+            .enumerate()
+            .map(|(i, col)| format!("h.{} AS \"src_{i}\"", quote_identifier(col.column_name())))
+            .chain(host_columns.iter().enumerate().map(|(i, col)| {
+                format!("h.{} AS \"dst_{i}\"", quote_identifier(col.column_name()))
+            }))
+            .collect::<Vec<String>>();
+    let not_null_conditions = host_columns
+        .iter()
+        .map(|col| format!("h.{} IS NOT NULL", quote_identifier(col.column_name())))
+        .collect::<Vec<String>>();
+
+    format!(
+        "SELECT {} FROM {} AS h WHERE {} AND {condition}",
+        selected.join(", "),
+        qualified_table_name(host_table),
+        not_null_conditions.join(" AND ")
+    )
+}
+
+/// Returns a query selecting the number of rows of the provided table
+/// estimated by the planner statistics, which is negative if the table has
+/// never been analyzed or spans no pages.
+///
+/// # Arguments
+///
+/// * `table` - The table whose number of rows should be estimated.
+///
+/// # Implementative details
+///
+/// Before PostgreSQL 14, the `reltuples` of a table which has never been
+/// analyzed is zero rather than negative, hence the tables without pages,
+/// which include these, are reported as having no estimate.
+pub(crate) fn estimated_row_count_query<T: TableLike>(table: &T) -> String {
+    format!(
+        "SELECT CASE WHEN relpages = 0 THEN -1 ELSE reltuples::bigint END AS \"estimate\" \
+         FROM pg_class WHERE oid = {}::regclass",
+        quote_literal(&qualified_table_name(table))
+    )
+}
+
+/// Returns a query selecting, for each row of the host table whose foreign key
+/// values are not null but do not match any row of the referenced table, its
+/// primary key followed by its foreign key values.
+///
+/// # Arguments
+///
+/// * `host_table` - The table hosting the foreign key.
+/// * `host_primary_key_columns` - The primary key columns of the host table.
+/// * `host_columns` - The foreign key columns of the host table.
+/// * `referenced_table` - The table referenced by the foreign key.
+/// * `referenced_columns` - The columns referenced by the foreign key.
+pub(crate) fn dangling_foreign_key_query<T: TableLike, C: ColumnLike>(
+    host_table: &T,
+    host_primary_key_columns: &[&C],
+    host_columns: &[&C],
+    referenced_table: &T,
+    referenced_columns: &[&C],
+) -> String {
+    let join_conditions = host_columns
+        .iter()
+        .zip(referenced_columns)
+        .map(|(host_col, referenced_col)| {
+            format!(
+                "r.{} = h.{}",
+                quote_identifier(referenced_col.column_name()),
+                quote_identifier(host_col.column_name())
+            )
+        })
+        .collect::<Vec<String>>();
+
+    foreign_key_values_query(
+        host_table,
+        host_primary_key_columns,
+        host_columns,
+        &format!(
+            "NOT EXISTS (SELECT 1 FROM {} AS r WHERE {})",
+            qualified_table_name(referenced_table),
+            join_conditions.join(" AND ")
+        ),
+    )
+}
+
+/// Returns a query selecting the primary key of the rows of the host table
+/// where some, but not all, of the foreign key columns are null.
+///
+/// # Arguments
+///
+/// * `host_table` - The table hosting the foreign key.
+/// * `host_primary_key_columns` - The primary key columns of the host table.
+/// * `host_columns` - The foreign key columns of the host table.
+pub(crate) fn partial_foreign_key_query<T: TableLike, C: ColumnLike>(
+    host_table: &T,
+    host_primary_key_columns: &[&C],
+    host_columns: &[&C],
+) -> String {
+    let column_conditions = |operator: &str, separator: &str| {
+        host_columns
+            .iter()
+            .map(|col| format!("h.{} {operator}", quote_identifier(col.column_name())))
+            .collect::<Vec<String>>()
+            .join(separator)
+    };
+
+    primary_key_query(
+        host_table,
+        host_primary_key_columns,
+        &format!(
+            "({}) AND ({})",
+            column_conditions("IS NULL", " OR "),
+            column_conditions("IS NOT NULL", " OR ")
+        ),
+    )
+}
+
+/// Returns a query selecting the primary key of the rows of the provided
+/// table whose provided column is not null, followed by the value of the
+/// column cast to text.
+///
+/// # Arguments
+///
+/// * `table` - The table containing the column.
+/// * `primary_key_columns` - The primary key columns of the table.
+/// * `column_name` - The name of the column.
+///
+/// # Implementative details
+///
+/// The cast lets the values of any type with a textual representation, such
+/// as the enumerated types whose OIDs differ in each database, be read as
+/// strings.
+pub(crate) fn text_values_query<T: TableLike, C: ColumnLike>(
+    table: &T,
+    primary_key_columns: &[&C],
+    column_name: &str,
+) -> String {
+    let selected = primary_key_columns
+        .iter()
+        .map(|col| format!("h.{}", quote_identifier(col.column_name())))
+        .chain(std::iter::once(format!("h.{}::text AS \"value\"", quote_identifier(column_name))))
+        .collect::<Vec<String>>();
+
+    format!(
+        "SELECT {} FROM {} AS h WHERE h.{} IS NOT NULL",
+        selected.join(", "),
+        qualified_table_name(table),
+        quote_identifier(column_name)
+    )
+}
+
+/// Returns a query counting the rows of the provided table where at least
+/// one of the provided columns is null.
+///
+/// # Arguments
+///
+/// * `table` - The table whose rows should be counted.
+/// * `column_names` - The names of the columns which may be null.
+pub(crate) fn null_references_count_query<T: TableLike>(
+    table: &T,
+    column_names: &[&str],
+) -> String {
+    let null_conditions = column_names
+        .iter()
+        .map(|column_name| format!("h.{} IS NULL", quote_identifier(column_name)))
+        .collect::<Vec<String>>();
+
+    format!(
+        "SELECT COUNT(*) AS \"count\" FROM {} AS h WHERE {}",
+        qualified_table_name(table),
+        null_conditions.join(" OR ")
+    )
+}
+
+/// Returns a query selecting the primary key of the rows of the provided
+/// table satisfying the provided condition.
+///
+/// # Arguments
+///
+/// * `table` - The table whose rows should be selected.
+/// * `primary_key_columns` - The primary key columns of the table.
+/// * `condition` - The condition on the rows of the table, which is aliased as
+///   `h`.
+pub(crate) fn primary_key_query<T: TableLike, C: ColumnLike>(
+    table: &T,
+    primary_key_columns: &[&C],
+    condition: &str,
+) -> String {
+    let selected = primary_key_columns
+        .iter()
+        .map(|col| format!("h.{}", quote_identifier(col.column_name())))
+        .collect::<Vec<String>>();
+
+    format!(
+        "SELECT {} FROM {} AS h WHERE {condition}",
+        selected.join(", "),
+        qualified_table_name(table)
+    )
+}
+
+/// Returns the condition selecting the rows whose modification column is
+/// more recent than the UNIX timestamp bound as the first parameter.
+///
+/// # Arguments
+///
+/// * `modification_column` - The name of the modification column of the table
+///   aliased as `h`.
+pub(crate) fn modified_since_condition(modification_column: &str) -> String {
+    format!("h.{} > to_timestamp($1)", quote_identifier(modification_column))
+}
+
+/// Returns a query selecting, among the provided columns of the provided
+/// table, the names of those whose type has the OID bound as the first
+/// parameter.
+///
+/// # Arguments
+///
+/// * `table` - The table whose columns should be inspected.
+/// * `columns` - The candidate columns of the table.
+pub(crate) fn columns_of_type_query<T: TableLike, C: ColumnLike>(
+    table: &T,
+    columns: &[&C],
+) -> String {
+    let column_names =
+        columns.iter().map(|col| quote_literal(col.column_name())).collect::<Vec<String>>();
+
+    format!(
+        "SELECT attname::text AS \"column\" FROM pg_attribute \
+         WHERE attrelid = {}::regclass AND atttypid = $1::oid AND attname IN ({}) \
+         ORDER BY attnum",
+        quote_literal(&qualified_table_name(table)),
+        column_names.join(", ")
+    )
+}
@@ -0,0 +1,364 @@
+//! Submodule providing the helpers used to write an export to a staging
+//! directory and to move it into place once complete.
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    grape::GRAPE_CONFIG_FILE_NAME, manifest::MANIFEST_FILE_NAME, statistics::STATISTICS_FILE_NAME,
+};
+
+/// The names of the files an export may write in the export directory.
+const EXPORT_FILE_NAMES: [&str; 8] = [
+    "node_classes.csv",
+    "nodes.csv",
+    "edge_classes.csv",
+    "edges.csv",
+    "edge_properties.csv",
+    MANIFEST_FILE_NAME,
+    GRAPE_CONFIG_FILE_NAME,
+    STATISTICS_FILE_NAME,
+];
+
+/// Returns the absolute form of the provided path, resolving `.` and `..` so
+/// that paths such as `.` name a directory.
+///
+/// # Arguments
+///
+/// * `path` - The export directory.
+///
+/// # Implementative details
+///
+/// An existing path is canonicalized, resolving symbolic links as well,
+/// while a path which does not exist yet is only made absolute.
+pub(crate) fn absolute(path: &Path) -> std::io::Result<PathBuf> {
+    if path.exists() { path.canonicalize() } else { std::path::absolute(path) }
+}
+
+/// Returns the sibling of the provided directory with the provided prefix
+/// and suffix around its name.
+///
+/// # Arguments
+///
+/// * `path` - The export directory.
+/// * `prefix` - The prefix of the sibling name.
+/// * `suffix` - The suffix of the sibling name.
+fn sibling(path: &Path, prefix: &str, suffix: &str) -> std::io::Result<PathBuf> {
+    let path = absolute(path)?;
+    let name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("{} does not name a directory", path.display()),
+        )
+    })?;
+    Ok(path.with_file_name(format!("{prefix}{}{suffix}", name.to_string_lossy())))
+}
+
+/// Returns the hidden sibling directory where the export to the provided
+/// directory is written before being moved into place, such as
+/// `.kg.partial` for `kg`.
+///
+/// # Arguments
+///
+/// * `path` - The export directory.
+pub(crate) fn staging_directory(path: &Path) -> std::io::Result<PathBuf> {
+    sibling(path, ".", ".partial")
+}
+
+/// Returns the sibling directory where the previous export to the provided
+/// directory is kept as a backup, such as `kg.backup` for `kg`.
+///
+/// # Arguments
+///
+/// * `path` - The export directory.
+pub(crate) fn backup_directory(path: &Path) -> std::io::Result<PathBuf> {
+    sibling(path, "", ".backup")
+}
+
+/// Returns whether the provided existing directory can be replaced as a
+/// whole, i.e. whether it only contains the files of a previous export, and
+/// is not the current directory.
+///
+/// # Arguments
+///
+/// * `path` - The absolute export directory.
+fn is_replaceable(path: &Path) -> std::io::Result<bool> {
+    if std::env::current_dir().and_then(|directory| directory.canonicalize()).ok().as_deref()
+        == Some(path)
+    {
+        return Ok(false);
+    }
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let is_export_file =
+            entry.file_name().to_str().is_some_and(|name| EXPORT_FILE_NAMES.contains(&name));
+        if !is_export_file || !entry.file_type()?.is_file() {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Checks that an export can be published to the provided directory, i.e.
+/// that it does not exist yet or can be replaced as a whole.
+///
+/// # Arguments
+///
+/// * `path` - The export directory.
+///
+/// # Errors
+///
+/// Returns an error of kind [`std::io::ErrorKind::DirectoryNotEmpty`] if the
+/// directory is the current directory or contains files other than those of
+/// an export, which cannot be replaced atomically.
+pub(crate) fn check_destination(path: &Path) -> std::io::Result<()> {
+    let path = absolute(path)?;
+    if path.exists() && !is_replaceable(&path)? {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::DirectoryNotEmpty,
+            format!(
+                "{} is the current directory or contains files other than those of an export",
+                path.display()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Exchanges the provided paths atomically.
+///
+/// # Arguments
+///
+/// * `first` - The first path.
+/// * `second` - The second path.
+///
+/// # Implementative details
+///
+/// The paths are exchanged with `renameat2` and the `RENAME_EXCHANGE` flag.
+/// On the file systems which do not support it, the paths are exchanged
+/// with three renames instead, between which the second path briefly does
+/// not exist.
+#[cfg(target_os = "linux")]
+fn exchange(first: &Path, second: &Path) -> std::io::Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let first_path = std::ffi::CString::new(first.as_os_str().as_bytes())?;
+    let second_path = std::ffi::CString::new(second.as_os_str().as_bytes())?;
+    // SAFETY: both paths are valid NUL-terminated strings, which outlive the
+    // call.
+    let result = unsafe {
+        libc::renameat2(
+            libc::AT_FDCWD,
+            first_path.as_ptr(),
+            libc::AT_FDCWD,
+            second_path.as_ptr(),
+            libc::RENAME_EXCHANGE,
+        )
+    };
+    if result == 0 {
+        return Ok(());
+    }
+    let error = std::io::Error::last_os_error();
+    if matches!(error.raw_os_error(), Some(libc::EINVAL | libc::ENOSYS)) {
+        exchange_with_renames(first, second)
+    } else {
+        Err(error)
+    }
+}
+
+/// Exchanges the provided paths.
+///
+/// # Arguments
+///
+/// * `first` - The first path.
+/// * `second` - The second path.
+///
+/// # Implementative details
+///
+/// Outside of Linux, the paths are exchanged with three renames, between
+/// which the second path briefly does not exist.
+#[cfg(not(target_os = "linux"))]
+fn exchange(first: &Path, second: &Path) -> std::io::Result<()> {
+    exchange_with_renames(first, second)
+}
+
+/// Exchanges the provided paths with three renames, through a temporary
+/// sibling of the second path.
+///
+/// # Arguments
+///
+/// * `first` - The first path.
+/// * `second` - The second path.
+fn exchange_with_renames(first: &Path, second: &Path) -> std::io::Result<()> {
+    let temporary = sibling(second, ".", ".old")?;
+    if temporary.exists() {
+        std::fs::remove_dir_all(&temporary)?;
+    }
+    std::fs::rename(second, &temporary)?;
+    std::fs::rename(first, second)?;
+    std::fs::rename(temporary, first)
+}
+
+/// Moves the complete export from the staging directory to the export
+/// directory, replacing the previous export if any.
+///
+/// # Arguments
+///
+/// * `staging` - The staging directory.
+/// * `path` - The export directory.
+/// * `keep_backup` - Whether to keep the previous export as a backup instead of
+///   removing it.
+///
+/// # Implementative details
+///
+/// When the export directory already exists, it is atomically exchanged
+/// with the staging directory, so that it always contains either the
+/// previous export or the new one. The previous export, left in the staging
+/// directory, is then removed or renamed to the backup directory.
+///
+/// # Errors
+///
+/// Returns an error if the export directory cannot be replaced as a whole,
+/// as checked by [`check_destination`], or if the renames fail.
+pub(crate) fn publish(staging: &Path, path: &Path, keep_backup: bool) -> std::io::Result<()> {
+    let path = absolute(path)?;
+    if !path.exists() {
+        return std::fs::rename(staging, path);
+    }
+    check_destination(&path)?;
+    exchange(staging, &path)?;
+    if keep_backup {
+        let backup = backup_directory(&path)?;
+        if backup.exists() {
+            std::fs::remove_dir_all(&backup)?;
+        }
+        std::fs::rename(staging, backup)
+    } else {
+        std::fs::remove_dir_all(staging)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns an empty temporary directory for the provided test.
+    fn test_directory(test_name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("sql2kg-staging-{}-{test_name}", std::process::id()));
+        if directory.exists() {
+            std::fs::remove_dir_all(&directory).unwrap();
+        }
+        std::fs::create_dir_all(&directory).unwrap();
+        directory.canonicalize().unwrap()
+    }
+
+    /// Writes a staging directory holding a `nodes.csv` file with the
+    /// provided content, returning its path.
+    fn write_staging(path: &Path, content: &str) -> PathBuf {
+        let staging = staging_directory(path).unwrap();
+        std::fs::create_dir_all(&staging).unwrap();
+        std::fs::write(staging.join("nodes.csv"), content).unwrap();
+        std::fs::write(staging.join(MANIFEST_FILE_NAME), "{}").unwrap();
+        staging
+    }
+
+    #[test]
+    fn test_sibling_directories() {
+        let directory = test_directory("siblings");
+        let path = directory.join("kg");
+        assert_eq!(staging_directory(&path).unwrap(), directory.join(".kg.partial"));
+        assert_eq!(backup_directory(&path).unwrap(), directory.join("kg.backup"));
+        // An existing path is resolved, so that `.` names the directory.
+        std::fs::create_dir(&path).unwrap();
+        assert_eq!(staging_directory(&path.join(".")).unwrap(), directory.join(".kg.partial"));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_is_replaceable() {
+        let directory = test_directory("replaceable");
+        assert!(is_replaceable(&directory).unwrap());
+
+        // An export written before the manifest was introduced only holds
+        // CSV files.
+        std::fs::write(directory.join("nodes.csv"), "node,node_class_ids\n").unwrap();
+        std::fs::write(directory.join("edges.csv"), "src,dst,edge_class\n").unwrap();
+        assert!(is_replaceable(&directory).unwrap());
+
+        std::fs::write(directory.join("notes.txt"), "keep me").unwrap();
+        assert!(!is_replaceable(&directory).unwrap());
+        std::fs::remove_file(directory.join("notes.txt")).unwrap();
+
+        std::fs::create_dir(directory.join("grape.json")).unwrap();
+        assert!(!is_replaceable(&directory).unwrap());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_publish_to_new_directory() {
+        let directory = test_directory("publish-new");
+        let path = directory.join("kg");
+        let staging = write_staging(&path, "new");
+
+        publish(&staging, &path, false).unwrap();
+        assert!(!staging.exists());
+        assert_eq!(std::fs::read_to_string(path.join("nodes.csv")).unwrap(), "new");
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_publish_replaces_previous_export() {
+        let directory = test_directory("publish-replace");
+        let path = directory.join("kg");
+        std::fs::create_dir(&path).unwrap();
+        std::fs::write(path.join("nodes.csv"), "old").unwrap();
+        std::fs::write(path.join("edges.csv"), "old").unwrap();
+        let staging = write_staging(&path, "new");
+
+        publish(&staging, &path, false).unwrap();
+        assert!(!staging.exists());
+        assert!(!backup_directory(&path).unwrap().exists());
+        assert_eq!(std::fs::read_to_string(path.join("nodes.csv")).unwrap(), "new");
+        // No file of the previous export is left next to the new ones.
+        assert!(!path.join("edges.csv").exists());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_publish_keeps_backup() {
+        let directory = test_directory("publish-backup");
+        let path = directory.join("kg");
+        std::fs::create_dir(&path).unwrap();
+        std::fs::write(path.join("nodes.csv"), "old").unwrap();
+        let backup = backup_directory(&path).unwrap();
+        std::fs::create_dir(&backup).unwrap();
+        std::fs::write(backup.join("nodes.csv"), "older").unwrap();
+        let staging = write_staging(&path, "new");
+
+        publish(&staging, &path, true).unwrap();
+        assert!(!staging.exists());
+        assert_eq!(std::fs::read_to_string(path.join("nodes.csv")).unwrap(), "new");
+        assert_eq!(std::fs::read_to_string(backup.join("nodes.csv")).unwrap(), "old");
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_publish_rejects_mixed_directory() {
+        let directory = test_directory("publish-mixed");
+        let path = directory.join("kg");
+        std::fs::create_dir(&path).unwrap();
+        std::fs::write(path.join("nodes.csv"), "old").unwrap();
+        std::fs::write(path.join("notes.txt"), "keep me").unwrap();
+        let staging = write_staging(&path, "new");
+
+        let error = check_destination(&path).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::DirectoryNotEmpty);
+        let error = publish(&staging, &path, false).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::DirectoryNotEmpty);
+        // Neither directory is touched.
+        assert_eq!(std::fs::read_to_string(path.join("nodes.csv")).unwrap(), "old");
+        assert_eq!(std::fs::read_to_string(path.join("notes.txt")).unwrap(), "keep me");
+        assert_eq!(std::fs::read_to_string(staging.join("nodes.csv")).unwrap(), "new");
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
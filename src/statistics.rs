@@ -0,0 +1,484 @@
+//! Submodule defining the per-class statistics computed after exporting a
+//! knowledge graph.
+
+use std::{
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use serde_json::{Value, json};
+
+/// The name of the statistics file written in the export directory.
+pub const STATISTICS_FILE_NAME: &str = "statistics.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+/// The minimum, maximum and mean degree of the nodes of a node class.
+pub struct DegreeStatistics {
+    /// The minimum degree.
+    min: usize,
+    /// The maximum degree.
+    max: usize,
+    /// The mean degree.
+    mean: f64,
+}
+
+impl DegreeStatistics {
+    /// Adds the provided degree of one more node to the statistics, whose
+    /// mean is temporarily the sum of the degrees.
+    ///
+    /// # Arguments
+    ///
+    /// * `degree` - The degree of the node.
+    /// * `first` - Whether the node is the first one of the node class.
+    fn add(&mut self, degree: usize, first: bool) {
+        self.min = if first { degree } else { self.min.min(degree) };
+        self.max = self.max.max(degree);
+        #[allow(clippy::cast_precision_loss)]
+        let degree = degree as f64;
+        self.mean += degree;
+    }
+
+    /// Turns the sum of the degrees into their mean.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_of_nodes` - The number of nodes of the node class.
+    fn finish(mut self, number_of_nodes: usize) -> Self {
+        if number_of_nodes > 0 {
+            #[allow(clippy::cast_precision_loss)]
+            let number_of_nodes = number_of_nodes as f64;
+            self.mean /= number_of_nodes;
+        }
+        self
+    }
+
+    /// Returns the minimum degree.
+    #[must_use]
+    pub fn min(&self) -> usize {
+        self.min
+    }
+
+    /// Returns the maximum degree.
+    #[must_use]
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Returns the mean degree.
+    #[must_use]
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Returns the JSON representation of the statistics.
+    fn to_json(self) -> Value {
+        json!({ "min": self.min, "max": self.max, "mean": self.mean })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The statistics of the nodes of a node class.
+pub struct NodeClassStatistics {
+    /// The name of the node class.
+    name: String,
+    /// The number of nodes whose first node class is this one.
+    number_of_nodes: usize,
+    /// The number of nodes without any incoming or outgoing edge.
+    number_of_isolated_nodes: usize,
+    /// The statistics of the in-degree of the nodes.
+    in_degree: DegreeStatistics,
+    /// The statistics of the out-degree of the nodes.
+    out_degree: DegreeStatistics,
+}
+
+impl NodeClassStatistics {
+    /// Returns the name of the node class.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the number of nodes whose first node class is this one.
+    #[must_use]
+    pub fn number_of_nodes(&self) -> usize {
+        self.number_of_nodes
+    }
+
+    /// Returns the number of nodes without any incoming or outgoing edge.
+    #[must_use]
+    pub fn number_of_isolated_nodes(&self) -> usize {
+        self.number_of_isolated_nodes
+    }
+
+    /// Returns the statistics of the in-degree of the nodes.
+    #[must_use]
+    pub fn in_degree(&self) -> DegreeStatistics {
+        self.in_degree
+    }
+
+    /// Returns the statistics of the out-degree of the nodes.
+    #[must_use]
+    pub fn out_degree(&self) -> DegreeStatistics {
+        self.out_degree
+    }
+
+    /// Returns the JSON representation of the statistics.
+    fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "number_of_nodes": self.number_of_nodes,
+            "number_of_isolated_nodes": self.number_of_isolated_nodes,
+            "in_degree": self.in_degree.to_json(),
+            "out_degree": self.out_degree.to_json(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The statistics of the edges of an edge class.
+pub struct EdgeClassStatistics {
+    /// The name of the edge class.
+    name: String,
+    /// The number of edges.
+    number_of_edges: usize,
+    /// The number of rows skipped because of null foreign key or categorical
+    /// values.
+    number_of_null_references: usize,
+}
+
+impl EdgeClassStatistics {
+    /// Returns the name of the edge class.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the number of edges.
+    #[must_use]
+    pub fn number_of_edges(&self) -> usize {
+        self.number_of_edges
+    }
+
+    /// Returns the number of rows of the host table which yield no edge
+    /// because at least one of their foreign key columns, or their
+    /// categorical column, is null.
+    #[must_use]
+    pub fn number_of_null_references(&self) -> usize {
+        self.number_of_null_references
+    }
+
+    /// Returns the JSON representation of the statistics.
+    fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "number_of_edges": self.number_of_edges,
+            "number_of_null_references": self.number_of_null_references,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/// The statistics of an export, per node class and per edge class.
+///
+/// # Implementative details
+///
+/// Each node is accounted to its first node class, i.e. to the most derived
+/// table it belongs to, and the degrees count the edges of every edge class.
+pub struct ExportStatistics {
+    /// The statistics of the node classes, in the order of their identifiers.
+    node_classes: Vec<NodeClassStatistics>,
+    /// The statistics of the edge classes, in the order of their identifiers.
+    edge_classes: Vec<EdgeClassStatistics>,
+}
+
+impl ExportStatistics {
+    /// Computes the statistics of the export in the provided directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory containing the finished `edges.csv`.
+    /// * `node_class_names` - The names of the node classes.
+    /// * `node_class_ids` - The first node class identifier of each node.
+    /// * `edge_class_names` - The names of the edge classes.
+    /// * `null_references` - The number of rows skipped because of null values,
+    ///   for each edge class.
+    ///
+    /// # Implementative details
+    ///
+    /// The edges are streamed back from `edges.csv`, so that the edges
+    /// written by the previous runs of a resumed export are also accounted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the edges file cannot be read or is malformed, or
+    /// if the number of null reference counts does not match the number of
+    /// edge classes.
+    pub(crate) fn compute(
+        directory: &Path,
+        node_class_names: Vec<String>,
+        node_class_ids: &[usize],
+        edge_class_names: Vec<String>,
+        null_references: &[usize],
+    ) -> Result<Self, crate::errors::Error> {
+        // Inverse edge classes need their own counts, which would otherwise
+        // be silently dropped when pairing them with the edge classes.
+        if null_references.len() != edge_class_names.len() {
+            return Err(crate::errors::Error::InvalidExport(format!(
+                "{} null reference counts were provided for {} edge classes",
+                null_references.len(),
+                edge_class_names.len()
+            )));
+        }
+        let mut in_degrees = vec![0_usize; node_class_ids.len()];
+        let mut out_degrees = vec![0_usize; node_class_ids.len()];
+        let mut number_of_edges = vec![0_usize; edge_class_names.len()];
+
+        let file = std::fs::File::open(directory.join("edges.csv"))?;
+        for (row, line) in BufReader::new(file).lines().skip(1).enumerate() {
+            let line = line?;
+            let ids = line.split(',').map(str::parse::<usize>).collect::<Result<Vec<usize>, _>>();
+            let (src_id, dst_id, edge_class_id) = match ids.as_deref() {
+                Ok(&[src_id, dst_id, edge_class_id])
+                    if src_id < node_class_ids.len()
+                        && dst_id < node_class_ids.len()
+                        && edge_class_id < edge_class_names.len() =>
+                {
+                    (src_id, dst_id, edge_class_id)
+                }
+                _ => {
+                    return Err(crate::errors::Error::InvalidExport(format!(
+                        "edges.csv: invalid edge on row {}",
+                        row + 1
+                    )));
+                }
+            };
+            out_degrees[src_id] += 1;
+            in_degrees[dst_id] += 1;
+            number_of_edges[edge_class_id] += 1;
+        }
+
+        let mut node_classes = node_class_names
+            .into_iter()
+            .map(|name| {
+                NodeClassStatistics {
+                    name,
+                    number_of_nodes: 0,
+                    number_of_isolated_nodes: 0,
+                    in_degree: DegreeStatistics::default(),
+                    out_degree: DegreeStatistics::default(),
+                }
+            })
+            .collect::<Vec<NodeClassStatistics>>();
+        for ((node_class_id, in_degree), out_degree) in
+            node_class_ids.iter().zip(in_degrees).zip(out_degrees)
+        {
+            let statistics = &mut node_classes[*node_class_id];
+            let first = statistics.number_of_nodes == 0;
+            statistics.number_of_nodes += 1;
+            if in_degree == 0 && out_degree == 0 {
+                statistics.number_of_isolated_nodes += 1;
+            }
+            statistics.in_degree.add(in_degree, first);
+            statistics.out_degree.add(out_degree, first);
+        }
+        for statistics in &mut node_classes {
+            statistics.in_degree = statistics.in_degree.finish(statistics.number_of_nodes);
+            statistics.out_degree = statistics.out_degree.finish(statistics.number_of_nodes);
+        }
+        let edge_classes = edge_class_names
+            .into_iter()
+            .zip(number_of_edges)
+            .zip(null_references)
+            .map(|((name, number_of_edges), number_of_null_references)| {
+                EdgeClassStatistics {
+                    name,
+                    number_of_edges,
+                    number_of_null_references: *number_of_null_references,
+                }
+            })
+            .collect();
+
+        Ok(Self { node_classes, edge_classes })
+    }
+
+    /// Returns the statistics of the node classes, in the order of their
+    /// identifiers.
+    #[must_use]
+    pub fn node_classes(&self) -> &[NodeClassStatistics] {
+        &self.node_classes
+    }
+
+    /// Returns the statistics of the edge classes, in the order of their
+    /// identifiers.
+    #[must_use]
+    pub fn edge_classes(&self) -> &[EdgeClassStatistics] {
+        &self.edge_classes
+    }
+
+    /// Returns the total number of nodes without any incoming or outgoing
+    /// edge.
+    #[must_use]
+    pub fn number_of_isolated_nodes(&self) -> usize {
+        self.node_classes.iter().map(NodeClassStatistics::number_of_isolated_nodes).sum()
+    }
+
+    /// Returns the JSON representation of the statistics.
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "node_classes": self
+                .node_classes
+                .iter()
+                .map(NodeClassStatistics::to_json)
+                .collect::<Vec<Value>>(),
+            "edge_classes": self
+                .edge_classes
+                .iter()
+                .map(EdgeClassStatistics::to_json)
+                .collect::<Vec<Value>>(),
+        })
+    }
+
+    /// Writes the statistics to the `statistics.json` file of the provided
+    /// directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The export directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn write(&self, directory: &Path) -> Result<(), crate::errors::Error> {
+        let file = std::fs::File::create(directory.join(STATISTICS_FILE_NAME))?;
+        let mut writer = std::io::BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, &self.to_json())?;
+        std::io::Write::flush(&mut writer)?;
+        writer.get_ref().sync_all()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns an empty temporary directory for the provided test.
+    fn test_directory(test_name: &str) -> std::path::PathBuf {
+        let directory = std::env::temp_dir()
+            .join(format!("sql2kg-statistics-{}-{test_name}", std::process::id()));
+        if directory.exists() {
+            std::fs::remove_dir_all(&directory).unwrap();
+        }
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    /// Computes the statistics of the provided edges, over two node classes
+    /// and two edge classes.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory where to write `edges.csv`.
+    /// * `edges` - The rows of `edges.csv`, without the header.
+    /// * `node_class_ids` - The first node class identifier of each node.
+    /// * `null_references` - The null reference counts of the edge classes.
+    fn compute(
+        directory: &Path,
+        edges: &str,
+        node_class_ids: &[usize],
+        null_references: &[usize],
+    ) -> Result<ExportStatistics, crate::errors::Error> {
+        std::fs::write(directory.join("edges.csv"), format!("src,dst,edge_class\n{edges}"))
+            .unwrap();
+        ExportStatistics::compute(
+            directory,
+            vec!["users".to_owned(), "posts".to_owned()],
+            node_class_ids,
+            vec![
+                "posts(author_id) -> users(id)".to_owned(),
+                "posts(editor_id) -> users(id)".to_owned(),
+            ],
+            null_references,
+        )
+    }
+
+    #[test]
+    fn test_degrees_and_isolated_nodes() {
+        let directory = test_directory("degrees");
+        // Nodes 0 to 2 are users and nodes 3 to 5 are posts: user 2 and post 5
+        // have no edges.
+        let statistics =
+            compute(&directory, "3,0,0\n4,0,0\n4,1,1\n", &[0, 0, 0, 1, 1, 1], &[1, 4]).unwrap();
+
+        let [users, posts] = statistics.node_classes() else {
+            panic!("expected two node classes");
+        };
+        assert_eq!(users.name(), "users");
+        assert_eq!(users.number_of_nodes(), 3);
+        assert_eq!(users.number_of_isolated_nodes(), 1);
+        assert_eq!((users.in_degree().min(), users.in_degree().max()), (0, 2));
+        assert!((users.in_degree().mean() - 1.0).abs() < f64::EPSILON);
+        assert_eq!((users.out_degree().min(), users.out_degree().max()), (0, 0));
+        assert!(users.out_degree().mean().abs() < f64::EPSILON);
+
+        assert_eq!(posts.number_of_nodes(), 3);
+        assert_eq!(posts.number_of_isolated_nodes(), 1);
+        assert_eq!((posts.out_degree().min(), posts.out_degree().max()), (0, 2));
+        assert!((posts.out_degree().mean() - 1.0).abs() < f64::EPSILON);
+
+        assert_eq!(statistics.number_of_isolated_nodes(), 2);
+        let [author, editor] = statistics.edge_classes() else {
+            panic!("expected two edge classes");
+        };
+        assert_eq!((author.number_of_edges(), author.number_of_null_references()), (2, 1));
+        assert_eq!((editor.number_of_edges(), editor.number_of_null_references()), (1, 4));
+
+        statistics.write(&directory).unwrap();
+        let written: Value = serde_json::from_str(
+            &std::fs::read_to_string(directory.join(STATISTICS_FILE_NAME)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(written, statistics.to_json());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_empty_node_class() {
+        let directory = test_directory("empty");
+        let statistics = compute(&directory, "", &[0, 0], &[0, 0]).unwrap();
+        let posts = &statistics.node_classes()[1];
+        assert_eq!(posts.number_of_nodes(), 0);
+        assert_eq!(posts.in_degree(), DegreeStatistics::default());
+        assert_eq!(statistics.number_of_isolated_nodes(), 2);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_out_of_range_edges() {
+        let directory = test_directory("out-of-range");
+        for edges in ["2,0,0\n", "0,2,0\n", "0,1,2\n", "0,1\n", "0,a,1\n"] {
+            let error =
+                compute(&directory, &format!("0,1,0\n{edges}"), &[0, 1], &[0, 0]).unwrap_err();
+            assert!(
+                matches!(&error, crate::errors::Error::InvalidExport(message) if message == "edges.csv: invalid edge on row 2"),
+                "{edges:?}: {error}"
+            );
+        }
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_mismatched_null_references() {
+        let directory = test_directory("null-references");
+        // An inverse orientation doubles the edge classes, whose counts must
+        // be provided as well.
+        for null_references in [&[0][..], &[0, 0, 0, 0][..]] {
+            assert!(matches!(
+                compute(&directory, "0,1,0\n", &[0, 1], null_references),
+                Err(crate::errors::Error::InvalidExport(_))
+            ));
+        }
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
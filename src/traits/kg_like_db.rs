@@ -1,8 +1,13 @@
 //! Submodule defining the `KGLikeDB` trait for knowledge graph-like databases.
 
-use std::io::Write;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    io::Write,
+};
 
-use diesel::{PgConnection, QueryDsl, RunQueryDsl, UntypedExpressionMethods, sql_types::Untyped};
+use diesel::{
+    Connection, PgConnection, QueryDsl, RunQueryDsl, UntypedExpressionMethods, sql_types::Untyped,
+};
 use diesel_dynamic_schema::{
     DynamicSelectClause,
     dynamic_value::{DynamicRow, NamedField},
@@ -10,7 +15,23 @@ use diesel_dynamic_schema::{
 use sql_traits::traits::{ColumnLike, DatabaseLike, ForeignKeyLike, TableLike};
 use time_requirements::{prelude::TimeTracker, task::Task};
 
-use crate::{edge_class::EdgeClass, node::Node, primary_key::PrimaryKey};
+use crate::{
+    audit::{IgnoredForeignKeyReason, IntegrityAudit},
+    checkpoint::Checkpoint,
+    csv::{CsvField, CsvWriter, parse_records, write_node_row},
+    edge_class::EdgeClass,
+    grape::GrapeConfig,
+    manifest::{Manifest, ManifestFile, graph_name},
+    node::Node,
+    node_name::{ClassName, Identifier, NodeName},
+    options::{DanglingReferencePolicy, EdgeOrientation, ExportOptions},
+    primary_key::{PrimaryKey, UnsupportedOid},
+    progress::{ExportPhase, PROGRESS_BATCH_SIZE},
+    property_value::PropertyValue,
+    report::ExportReport,
+    schema_graph::{SchemaEdgeClass, SchemaNodeClass},
+    statistics::ExportStatistics,
+};
 
 /// A trait representing knowledge graph-like database functionalities.
 pub trait KGLikeDB: DatabaseLike {
@@ -27,9 +48,12 @@ pub trait KGLikeDB: DatabaseLike {
     ///
     /// ```plain
     /// users(1)
+    /// tags('rust')
     /// comments(3995db4d-2b2d-4c0e-8c5f-eeeb1efbd315, 8b1756b7-58b8-40cc-81b3-46ba68c8e964)
     /// ```
     ///
+    /// The names are unambiguous and can be parsed back into a [`NodeName`].
+    ///
     /// In order to avoid duplicated nodes, if a table is a descendant of
     /// another table in an inheritance hierarchy, only the rows of the most
     /// derived tables are returned, i.e. only the nodes of a leaf table are
@@ -37,50 +61,79 @@ pub trait KGLikeDB: DatabaseLike {
     fn nodes<'conn, 'db>(
         &'db self,
         conn: &'conn mut PgConnection,
-    ) -> impl Iterator<Item = Result<Vec<Node<'db, Self>>, diesel::result::Error>> + 'conn
+    ) -> impl Iterator<Item = Result<Vec<Node<'db, Self>>, crate::errors::Error>> + 'conn
     where
         'db: 'conn,
     {
-        self.tables().filter(|table| table.has_primary_key(self)).map(move |table| {
-            // For each table, we create a SQL diesel query to select the primary key
-            // columns and convert them within the query into the standardized
-            // node name format.
+        self.nodes_from(conn, 0)
+    }
 
-            let table_name = table.table_name();
-            let primary_key_columns =
-                table.primary_key_columns(self).collect::<Vec<&Self::Column>>();
+    /// Iterate over the nodes in the knowledge graph, skipping the provided
+    /// number of tables with a primary key without querying them.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A reference to the database connection.
+    /// * `first_table` - The number of tables with a primary key to skip.
+    fn nodes_from<'conn, 'db>(
+        &'db self,
+        conn: &'conn mut PgConnection,
+        first_table: usize,
+    ) -> impl Iterator<Item = Result<Vec<Node<'db, Self>>, crate::errors::Error>> + 'conn
+    where
+        'db: 'conn,
+    {
+        self.tables().filter(|table| table.has_primary_key(self)).skip(first_table).map(
+            move |table| {
+                // For each table, we create a SQL diesel query to select the primary key
+                // columns and convert them within the query into the standardized
+                // node name format.
 
-            let dynamic_table = diesel_dynamic_schema::table(table_name);
-            let mut select = DynamicSelectClause::new();
+                let table_name = table.table_name();
+                let primary_key_columns =
+                    table.primary_key_columns(self).collect::<Vec<&Self::Column>>();
 
-            // Store columns and their names to reuse them for selection and ordering
-            let columns: Vec<_> = primary_key_columns
-                .iter()
-                .map(|col| dynamic_table.column::<Untyped, _>(col.column_name()))
-                .collect();
+                let dynamic_table = diesel_dynamic_schema::table(table_name);
+                let mut select = DynamicSelectClause::new();
 
-            for col in &columns {
-                select.add_field(*col);
-            }
+                // Store columns and their names to reuse them for selection and ordering
+                let columns: Vec<_> = primary_key_columns
+                    .iter()
+                    .map(|col| dynamic_table.column::<Untyped, _>(col.column_name()))
+                    .collect();
 
-            let results: Vec<DynamicRow<NamedField<PrimaryKey>>> =
-                dynamic_table.select(select).load(conn)?;
-            let mut nodes: Vec<Node<'_, Self>> = results
-                .into_iter()
-                .map(|row| {
-                    let primary_keys: Vec<PrimaryKey> = row.into();
-                    Node::new(table, primary_keys.into())
-                })
-                .collect();
+                for col in &columns {
+                    select.add_field(*col);
+                }
 
-            // Ideally, the nodes should be queries with `ORDER BY` clause to
-            // ensure consistent ordering, but since we cannot guarantee that
-            // all primary key columns can be ordered using the expected collation,
-            // we sort them in Rust instead.
-            nodes.sort_unstable();
+                let results: Vec<DynamicRow<NamedField<PrimaryKey>>> =
+                    match dynamic_table.select(select).load(conn) {
+                        Ok(results) => results,
+                        Err(error) => {
+                            return Err(query_error::<Self>(
+                                conn,
+                                &[(table, primary_key_columns.as_slice())],
+                                error,
+                            ));
+                        }
+                    };
+                let mut nodes: Vec<Node<'_, Self>> = results
+                    .into_iter()
+                    .map(|row| {
+                        let primary_keys: Vec<PrimaryKey> = row.into();
+                        Node::new(table, primary_keys.into())
+                    })
+                    .collect();
 
-            Ok(nodes)
-        })
+                // Ideally, the nodes should be queries with `ORDER BY` clause to
+                // ensure consistent ordering, but since we cannot guarantee that
+                // all primary key columns can be ordered using the expected collation,
+                // we sort them in Rust instead.
+                nodes.sort_unstable();
+
+                Ok(nodes)
+            },
+        )
     }
 
     /// Returns the number of nodes in the knowledge graph.
@@ -91,8 +144,9 @@ pub trait KGLikeDB: DatabaseLike {
     ///
     /// # Errors
     ///
-    /// Returns a `diesel::result::Error` if the database query fails.
-    fn number_of_nodes(&self, conn: &mut PgConnection) -> Result<usize, diesel::result::Error> {
+    /// Returns an [`Error::Query`](crate::errors::Error::Query) naming the
+    /// table whose count failed.
+    fn number_of_nodes(&self, conn: &mut PgConnection) -> Result<usize, crate::errors::Error> {
         let mut total = 0;
 
         for table in self.tables() {
@@ -100,46 +154,93 @@ pub trait KGLikeDB: DatabaseLike {
                 continue;
             }
 
-            let count: i64 =
-                diesel_dynamic_schema::table(table.table_name()).count().get_result(conn)?;
+            let query_error =
+                |source| crate::errors::Error::Query { table: node_class_name(table), source };
+            let count: i64 = diesel_dynamic_schema::table(table.table_name())
+                .count()
+                .get_result(conn)
+                .map_err(query_error)?;
 
             total += usize::try_from(count).map_err(|_| {
-                diesel::result::Error::DeserializationError(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    "Count value too large for usize",
+                query_error(diesel::result::Error::DeserializationError(Box::new(
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "Count value too large for usize",
+                    ),
                 )))
             })?;
         }
         Ok(total)
     }
 
+    /// Returns an estimate of the number of nodes in the knowledge graph,
+    /// based on the planner statistics of the tables.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to the database connection.
+    ///
+    /// # Implementative details
+    ///
+    /// The number of rows of each table is read from `pg_class.reltuples`,
+    /// which is maintained by `VACUUM`, `ANALYZE` and `CREATE INDEX` and
+    /// therefore does not require scanning the table, but may be outdated.
+    /// Tables which have never been analyzed, or which span no pages as
+    /// the tables never analyzed before PostgreSQL 14 do, have no estimate
+    /// and are counted exactly instead. The total saturates at `usize::MAX`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Query`](crate::errors::Error::Query) naming the
+    /// table whose estimate or count failed.
+    fn estimated_number_of_nodes(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Result<usize, crate::errors::Error> {
+        let mut total: usize = 0;
+
+        for table in self.tables() {
+            if !table.has_primary_key(self) {
+                continue;
+            }
+
+            let estimate = load_bigint(conn, &crate::sql::estimated_row_count_query(table))
+                .map_err(|source| {
+                    crate::errors::Error::Query { table: node_class_name(table), source }
+                })?
+                .and_then(|estimate| usize::try_from(estimate).ok());
+
+            let rows = match estimate {
+                Some(estimate) => estimate,
+                None => {
+                    let count: i64 = diesel_dynamic_schema::table(table.table_name())
+                        .count()
+                        .get_result(conn)
+                        .map_err(|source| {
+                            crate::errors::Error::Query { table: node_class_name(table), source }
+                        })?;
+                    usize::try_from(count).unwrap_or(usize::MAX)
+                }
+            };
+            total = total.saturating_add(rows);
+        }
+        Ok(total)
+    }
+
     /// Iterate over the edges classes in the knowledge graph.
     ///
     /// # Implementative details
     ///
     /// An edge in this context is a foreign key relationship between two
     /// tables, based on some host table's foreign key columns pointing to a
-    /// referenced table's primary key columns. Each edge class is represented
-    /// as a tuple of the host table name, the referenced table name, and
-    /// the foreign key column names.
+    /// referenced table's primary key columns, or to any other unique columns
+    /// of a referenced table which has a primary key. Only the host tables
+    /// with a primary key define edge classes, as the rows of the other
+    /// tables are not nodes. Each edge class is
+    /// represented as a tuple of the host table name, the referenced table
+    /// name, and the foreign key column names.
     fn edge_classes(&self) -> impl Iterator<Item = EdgeClass<'_, Self>> {
-        self.tables().flat_map(move |t| {
-            let mut edge_classes = t
-                .foreign_keys(self)
-                .filter_map(move |fk| {
-                    // We disregard foreign keys that do not point to primary key columns
-                    // in the referenced table.
-                    if !fk.is_referenced_primary_key(self) {
-                        return None;
-                    }
-
-                    let host_columns = fk.host_columns(self).collect::<Vec<_>>();
-                    Some(EdgeClass::new(t, host_columns))
-                })
-                .collect::<Vec<EdgeClass<'_, Self>>>();
-            edge_classes.sort_unstable();
-            edge_classes
-        })
+        self.tables().flat_map(move |table| table_edge_classes(self, table))
     }
 
     /// Iterate over the edges in the knowledge graph.
@@ -147,14 +248,64 @@ pub trait KGLikeDB: DatabaseLike {
     /// # Arguments
     ///
     /// * `conn` - A mutable reference to the database connection.
-    #[allow(clippy::too_many_lines, clippy::type_complexity)]
+    ///
+    /// # Implementative details
+    ///
+    /// When a foreign key references unique columns other than the primary key
+    /// of the referenced table, the referenced table is joined to resolve the
+    /// primary key of the referenced row. Rows whose foreign key values do not
+    /// match any referenced row are not returned, and are instead listed as
+    /// dangling references by [`edges_from`](KGLikeDB::edges_from).
+    #[allow(clippy::type_complexity)]
     fn edges<'conn, 'db>(
         &'db self,
         conn: &'conn mut PgConnection,
     ) -> impl Iterator<
         Item = Result<
             Vec<(Node<'db, Self>, Node<'db, Self>, EdgeClass<'db, Self>)>,
-            diesel::result::Error,
+            crate::errors::Error,
+        >,
+    > + 'conn
+    where
+        'db: 'conn,
+    {
+        self.edges_from(conn, 0).map(|result| result.map(|(_, edges, _)| edges))
+    }
+
+    /// Iterate over the edges in the knowledge graph, grouped by edge class,
+    /// skipping the provided number of edge classes without querying them.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to the database connection.
+    /// * `first_edge_class` - The number of edge classes to skip.
+    ///
+    /// # Implementative details
+    ///
+    /// The edge classes are visited in the order of the tables and of their
+    /// foreign keys, which is stable across calls but may differ from the
+    /// sorted order of [`edge_classes`](KGLikeDB::edge_classes).
+    ///
+    /// Along with the edges of each edge class, the rows whose foreign key
+    /// references unique columns other than the primary key, and whose
+    /// foreign key values do not match any row of the referenced table, are
+    /// returned as dangling references, together with their foreign key
+    /// values, since the primary key of the referenced row is unknown. The
+    /// dangling references of foreign keys referencing the primary key are
+    /// instead returned as edges towards nodes which do not exist.
+    #[allow(clippy::too_many_lines, clippy::type_complexity)]
+    fn edges_from<'conn, 'db>(
+        &'db self,
+        conn: &'conn mut PgConnection,
+        first_edge_class: usize,
+    ) -> impl Iterator<
+        Item = Result<
+            (
+                EdgeClass<'db, Self>,
+                Vec<(Node<'db, Self>, Node<'db, Self>, EdgeClass<'db, Self>)>,
+                Vec<(Node<'db, Self>, PrimaryKey)>,
+            ),
+            crate::errors::Error,
         >,
     > + 'conn
     where
@@ -166,12 +317,15 @@ pub trait KGLikeDB: DatabaseLike {
                     t.primary_key_columns(self).collect::<Vec<&Self::Column>>();
 
                 t.foreign_keys(self).filter_map(move |fk| {
-                    if !fk.is_referenced_primary_key(self) || host_primary_key_columns.is_empty() {
+                    if host_primary_key_columns.is_empty()
+                        || !fk.referenced_table(self).has_primary_key(self)
+                    {
                         return None;
                     }
                     Some((fk, host_primary_key_columns.clone()))
                 })
             })
+            .skip(first_edge_class)
             .map(move |(fk, host_pk_columns)| {
                 // We query the host table to get all rows and their foreign key values,
                 // then we create the corresponding nodes for both the host and
@@ -180,12 +334,69 @@ pub trait KGLikeDB: DatabaseLike {
                 let referenced_table = fk.referenced_table(self);
 
                 let host_fk_columns = fk.host_columns(self).collect::<Vec<&Self::Column>>();
-                let edge_class = EdgeClass::new(host_table, host_fk_columns.clone());
+                let referenced_columns =
+                    fk.referenced_columns(self).collect::<Vec<&Self::Column>>();
+                let edge_class = EdgeClass::new(
+                    host_table,
+                    host_fk_columns.clone(),
+                    referenced_table,
+                    referenced_columns.clone(),
+                    fk.foreign_key_name(),
+                );
+
+                let referenced_pk_columns =
+                    referenced_table.primary_key_columns(self).collect::<Vec<&Self::Column>>();
+                let host_key_columns = host_pk_columns
+                    .iter()
+                    .chain(&host_fk_columns)
+                    .copied()
+                    .collect::<Vec<&Self::Column>>();
+
+                if !fk.is_referenced_primary_key(self) {
+                    // The foreign key points to unique columns other than the primary
+                    // key, so we join the referenced table to retrieve the primary key
+                    // of the referenced rows.
+                    let results: Vec<DynamicRow<NamedField<PropertyValue>>> =
+                        match diesel::sql_query(crate::sql::referenced_primary_key_query(
+                            host_table,
+                            &host_pk_columns,
+                            &host_fk_columns,
+                            referenced_table,
+                            &referenced_columns,
+                            &referenced_pk_columns,
+                            None,
+                        ))
+                        .load(conn)
+                        {
+                            Ok(results) => results,
+                            Err(error) => {
+                                return Err(query_error::<Self>(
+                                    conn,
+                                    &[
+                                        (host_table, host_key_columns.as_slice()),
+                                        (referenced_table, referenced_pk_columns.as_slice()),
+                                    ],
+                                    error,
+                                ));
+                            }
+                        };
+                    let (edges, dangling_references) = split_referenced_rows::<Self>(
+                        results,
+                        (host_table, &host_pk_columns, &host_fk_columns),
+                        (referenced_table, &referenced_pk_columns),
+                    )?;
+                    let edges = edges
+                        .into_iter()
+                        .map(|(host_node, referenced_node)| {
+                            (host_node, referenced_node, edge_class.clone())
+                        })
+                        .collect::<Vec<_>>();
+                    return Ok((edge_class, edges, dangling_references));
+                }
 
                 let dynamic_table = diesel_dynamic_schema::table(host_table.table_name());
-                let columns = host_pk_columns
+                let columns = host_key_columns
                     .iter()
-                    .chain(host_fk_columns.iter())
                     .map(|col| dynamic_table.column::<Untyped, _>(col.column_name()))
                     .collect::<Vec<_>>();
                 let select = columns.iter().collect::<DynamicSelectClause<_, _>>();
@@ -198,7 +409,16 @@ pub trait KGLikeDB: DatabaseLike {
                     query = query.filter(col.is_not_null());
                 }
 
-                let results: Vec<DynamicRow<NamedField<PrimaryKey>>> = query.load(conn)?;
+                let results: Vec<DynamicRow<NamedField<PrimaryKey>>> = match query.load(conn) {
+                    Ok(results) => results,
+                    Err(error) => {
+                        return Err(query_error::<Self>(
+                            conn,
+                            &[(host_table, host_key_columns.as_slice())],
+                            error,
+                        ));
+                    }
+                };
 
                 let pk_len = host_pk_columns.len();
 
@@ -217,10 +437,256 @@ pub trait KGLikeDB: DatabaseLike {
                     })
                     .collect::<Vec<_>>();
 
-                Ok(edges)
+                Ok((edge_class, edges, Vec::new()))
             })
     }
 
+    /// Returns the values of the provided columns for each row of the provided
+    /// table, indexed by the primary key of the row.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to the database connection.
+    /// * `table` - The table whose rows should be read.
+    /// * `column_names` - The names of the columns to read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if one of the columns does not exist in the table or if
+    /// the database query fails.
+    fn row_properties(
+        &self,
+        conn: &mut PgConnection,
+        table: &Self::Table,
+        column_names: &[String],
+    ) -> Result<HashMap<PrimaryKey, Vec<PropertyValue>>, crate::errors::Error> {
+        for column_name in column_names {
+            if !table.columns(self).any(|col| col.column_name() == column_name) {
+                return Err(crate::errors::Error::ColumnNotFound(format!(
+                    "{}.{column_name}",
+                    table.table_name()
+                )));
+            }
+        }
+
+        let primary_key_columns = table.primary_key_columns(self).collect::<Vec<&Self::Column>>();
+        let dynamic_table = diesel_dynamic_schema::table(table.table_name());
+        let columns = primary_key_columns
+            .iter()
+            .map(|col| col.column_name())
+            .chain(column_names.iter().map(String::as_str))
+            .map(|column_name| dynamic_table.column::<Untyped, _>(column_name))
+            .collect::<Vec<_>>();
+        let select = columns.iter().collect::<DynamicSelectClause<_, _>>();
+
+        let results: Vec<DynamicRow<NamedField<PropertyValue>>> =
+            match dynamic_table.select(select).load(conn) {
+                Ok(results) => results,
+                Err(error) => {
+                    return Err(query_error::<Self>(
+                        conn,
+                        &[(table, primary_key_columns.as_slice())],
+                        error,
+                    ));
+                }
+            };
+
+        let pk_len = primary_key_columns.len();
+        let mut properties = HashMap::with_capacity(results.len());
+        for row in results {
+            let mut vals: Vec<PropertyValue> = row.into();
+            let property_vals = vals.split_off(pk_len);
+            let pk_vals = primary_keys::<Self>(table, &primary_key_columns, vals)?;
+            properties.insert(pk_vals.into(), property_vals);
+        }
+
+        Ok(properties)
+    }
+
+    /// Audits the referential integrity of the foreign keys of the database,
+    /// without exporting it.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to the database connection.
+    ///
+    /// # Implementative details
+    ///
+    /// For each foreign key hosted by a table with a primary key, the audit
+    /// reports the rows whose foreign key values do not match any row of the
+    /// referenced table, which the export would treat as dangling
+    /// references, and the rows whose composite foreign key is partially
+    /// null, which the database does not check and the export skips. The
+    /// foreign keys which do not define an edge class, because either table
+    /// has no primary key, are reported as ignored.
+    ///
+    /// Foreign keys referencing a unique constraint other than the primary
+    /// key are not ignored: since they are resolved to the primary key of the
+    /// referenced row, they define edge classes like any other foreign key,
+    /// hence only the tables without a primary key make a foreign key
+    /// ignored, as described by
+    /// [`IgnoredForeignKeyReason`](crate::audit::IgnoredForeignKeyReason).
+    ///
+    /// The rows of a host table without a primary key cannot be reported,
+    /// as they are not nodes, hence its foreign keys are skipped by the
+    /// dangling and partially null checks and are only reported as ignored.
+    /// A foreign key referencing a table without a primary key is instead
+    /// both reported as ignored and checked, as its referencing rows are
+    /// nodes. As for the export, all of the queries run within a single
+    /// `REPEATABLE READ`, read-only transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Query`](crate::errors::Error::Query) or an
+    /// [`Error::UnsupportedKeyType`](crate::errors::Error::UnsupportedKeyType)
+    /// if the queries fail.
+    fn audit_referential_integrity(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Result<IntegrityAudit<'_, Self>, crate::errors::Error> {
+        snapshot_transaction(
+            conn,
+            |conn| -> Result<IntegrityAudit<'_, Self>, crate::errors::Error> {
+                let mut audit = IntegrityAudit::default();
+                for fk in self.tables().flat_map(|table| table.foreign_keys(self)) {
+                    let host_table = fk.host_table(self);
+                    let referenced_table = fk.referenced_table(self);
+                    let host_pk_columns =
+                        host_table.primary_key_columns(self).collect::<Vec<&Self::Column>>();
+                    let host_columns = fk.host_columns(self).collect::<Vec<&Self::Column>>();
+                    let referenced_columns =
+                        fk.referenced_columns(self).collect::<Vec<&Self::Column>>();
+                    let edge_class = EdgeClass::new(
+                        host_table,
+                        host_columns.clone(),
+                        referenced_table,
+                        referenced_columns.clone(),
+                        fk.foreign_key_name(),
+                    );
+
+                    // The dangling and partially null rows are reported as
+                    // nodes, which the rows of a host without a primary key
+                    // are not, hence its foreign keys are not checked.
+                    if host_pk_columns.is_empty() {
+                        audit.add_ignored_foreign_key(
+                            edge_class,
+                            IgnoredForeignKeyReason::HostWithoutPrimaryKey,
+                        );
+                        continue;
+                    }
+                    if !referenced_table.has_primary_key(self) {
+                        audit.add_ignored_foreign_key(
+                            edge_class.clone(),
+                            IgnoredForeignKeyReason::ReferencedWithoutPrimaryKey,
+                        );
+                    }
+
+                    let results: Vec<DynamicRow<NamedField<PrimaryKey>>> =
+                        match diesel::sql_query(crate::sql::dangling_foreign_key_query(
+                            host_table,
+                            &host_pk_columns,
+                            &host_columns,
+                            referenced_table,
+                            &referenced_columns,
+                        ))
+                        .load(conn)
+                        {
+                            Ok(results) => results,
+                            Err(error) => {
+                                let host_key_columns = host_pk_columns
+                                    .iter()
+                                    .chain(&host_columns)
+                                    .copied()
+                                    .collect::<Vec<&Self::Column>>();
+                                return Err(query_error::<Self>(
+                                    conn,
+                                    &[(host_table, host_key_columns.as_slice())],
+                                    error,
+                                ));
+                            }
+                        };
+                    for row in results {
+                        let mut values: Vec<PrimaryKey> = row.into();
+                        let fk_values = values.split_off(host_pk_columns.len());
+                        audit.add_dangling_foreign_key(
+                            edge_class.clone(),
+                            Node::new(host_table, values.into()),
+                            fk_values.into(),
+                        );
+                    }
+
+                    if host_columns.len() > 1 {
+                        let results: Vec<DynamicRow<NamedField<PrimaryKey>>> =
+                            match diesel::sql_query(crate::sql::partial_foreign_key_query(
+                                host_table,
+                                &host_pk_columns,
+                                &host_columns,
+                            ))
+                            .load(conn)
+                            {
+                                Ok(results) => results,
+                                Err(error) => {
+                                    return Err(query_error::<Self>(
+                                        conn,
+                                        &[(host_table, host_pk_columns.as_slice())],
+                                        error,
+                                    ));
+                                }
+                            };
+                        for row in results {
+                            let primary_keys: Vec<PrimaryKey> = row.into();
+                            audit.add_partial_foreign_key(
+                                edge_class.clone(),
+                                Node::new(host_table, primary_keys.into()),
+                            );
+                        }
+                    }
+                }
+                Ok(audit)
+            },
+        )
+    }
+
+    /// Returns the fingerprint of the schema of the database, as the
+    /// hex-encoded SHA-256 digest of a canonical description of its tables.
+    ///
+    /// # Implementative details
+    ///
+    /// The description lists, for each table, its columns, its primary key
+    /// columns, the tables it extends and its foreign keys. Two databases
+    /// sharing the same fingerprint therefore produce knowledge graphs with
+    /// the same node and edge classes.
+    fn schema_fingerprint(&self) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        for table in self.tables() {
+            hasher.update(format!("table {}\n", node_class_name(table)));
+            for column in table.columns(self) {
+                hasher.update(format!("column {}\n", Identifier(column.column_name())));
+            }
+            for column in table.primary_key_columns(self) {
+                hasher.update(format!("primary key {}\n", Identifier(column.column_name())));
+            }
+            for ancestor in table.ancestral_extended_tables(self) {
+                hasher.update(format!("extends {}\n", node_class_name(ancestor)));
+            }
+            for fk in table.foreign_keys(self) {
+                hasher.update(format!(
+                    "foreign key {}\n",
+                    EdgeClass::<Self>::new(
+                        table,
+                        fk.host_columns(self).collect(),
+                        fk.referenced_table(self),
+                        fk.referenced_columns(self).collect(),
+                        fk.foreign_key_name(),
+                    )
+                ));
+            }
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
     /// Writes out the CSVs representing the knowledge graph at the given path.
     ///
     /// # Arguments
@@ -236,108 +702,1537 @@ pub trait KGLikeDB: DatabaseLike {
         &self,
         conn: &mut PgConnection,
         path: &std::path::Path,
+    ) -> Result<TimeTracker, crate::errors::Error> {
+        let report = self.write_kg_csvs_with_options(conn, path, &ExportOptions::default())?;
+        Ok(report.into_time_tracker())
+    }
+
+    /// Writes out the CSVs representing the knowledge graph at the given path,
+    /// using the provided export options.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to the database connection.
+    /// * `path` - The path where to write the CSV files.
+    /// * `options` - The options customizing the export.
+    ///
+    /// # Implementative details
+    ///
+    /// Next to the CSV files, a `manifest.json` file is written, recording the
+    /// number of rows and the SHA-256 digest of each file, the number of
+    /// nodes, node classes, edges and edge classes, the export options and
+    /// the [schema fingerprint](KGLikeDB::schema_fingerprint), together with
+    /// a `grape.json` file listing the arguments of grape's `Graph.from_csv`
+    /// needed to load the export.
+    ///
+    /// After the nodes of each table and the edges of each edge class are
+    /// written, the progress is persisted in a `checkpoint.json` file, which
+    /// is removed once the export completes. When requested with
+    /// [`ExportOptions::with_resume`], an export to the same path with the
+    /// same schema and options as an interrupted one resumes from its last
+    /// completed table or edge class, truncating the partial files, and its
+    /// time tracker also covers the previous runs. The data should not
+    /// change between the runs. Otherwise, the export restarts from scratch.
+    ///
+    /// The files are written to the hidden `.{name}.partial` sibling
+    /// directory, where the checkpoint of an interrupted export is also kept,
+    /// and the directory is atomically exchanged with the provided path only
+    /// once every file has been flushed to disk. Hence, a failed export never
+    /// leaves a mix of old and new files at the provided path. The previous
+    /// export is removed, or kept in the `{name}.backup` sibling directory
+    /// when requested with [`ExportOptions::with_backup`]. The provided path
+    /// must not be the current directory nor contain files other than those
+    /// of a previous export.
+    ///
+    /// All of the queries run within a single `REPEATABLE READ`, read-only
+    /// transaction, so that the nodes and edges are read from the same
+    /// snapshot of the database even while it is being written to. The
+    /// snapshot exported by another transaction can be imported with
+    /// [`ExportOptions::with_snapshot`]. When the connection is already
+    /// within a transaction, the export runs within a savepoint of it
+    /// instead, reading from its snapshot only if it is a `REPEATABLE READ`
+    /// transaction, and no snapshot can be imported. A resumed export reads
+    /// from a new snapshot.
+    ///
+    /// The edges can also be emitted together with their reverse edges, or
+    /// the export flagged as undirected in `grape.json`, as requested with
+    /// [`ExportOptions::with_edge_orientation`].
+    ///
+    /// When requested with [`ExportOptions::with_statistics`], the per-class
+    /// statistics of the export are computed once every file is written,
+    /// returned in the report and written to a `statistics.json` file.
+    ///
+    /// When the [cancellation token](ExportOptions::with_cancellation_token)
+    /// is cancelled, the export stops before the next table, edge class or
+    /// batch of rows, and the staging directory is removed together with its
+    /// checkpoint, leaving any previous export at the provided path in place.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the database queries fail,
+    /// [`Error::SnapshotImport`](crate::errors::Error::SnapshotImport) if
+    /// the snapshot cannot be imported,
+    /// [`Error::ResumedNodeNotFound`](crate::errors::Error::ResumedNodeNotFound)
+    /// if a node written by an interrupted export belongs to a table which
+    /// is no longer in the database,
+    /// [`Error::SnapshotWithinTransaction`](crate::errors::Error::SnapshotWithinTransaction)
+    /// if a snapshot is requested while the connection is already within a
+    /// transaction, if the provided path is the current
+    /// directory or contains files other than those of an export, if writing
+    /// to the files fails, if a table of the options does not exist or its
+    /// unqualified name is ambiguous, if a categorical column does not
+    /// exist or belongs to a table without a primary key, if a dangling
+    /// reference is encountered while using the
+    /// [`DanglingReferencePolicy::Fail`] policy, or
+    /// [`Error::Cancelled`](crate::errors::Error::Cancelled) if the export
+    /// is cancelled.
+    fn write_kg_csvs_with_options(
+        &self,
+        conn: &mut PgConnection,
+        path: &std::path::Path,
+        options: &ExportOptions,
+    ) -> Result<ExportReport<'_, Self>, crate::errors::Error> {
+        // A snapshot can only be imported by the first statement of a
+        // transaction, hence not within a savepoint of the caller's one.
+        if options.snapshot().is_some() && is_within_transaction(conn)? {
+            return Err(crate::errors::Error::SnapshotWithinTransaction);
+        }
+        snapshot_transaction(conn, |conn| -> Result<ExportReport<'_, Self>, crate::errors::Error> {
+            if let Some(snapshot) = options.snapshot() {
+                diesel::sql_query(format!(
+                    "SET TRANSACTION SNAPSHOT {}",
+                    crate::sql::quote_literal(snapshot)
+                ))
+                .execute(conn)
+                .map_err(|source| {
+                    crate::errors::Error::SnapshotImport { snapshot: snapshot.to_owned(), source }
+                })?;
+            }
+            write_kg_csvs_in_transaction(self, conn, path, options)
+        })
+        .inspect_err(|error| {
+            // A cancelled export is not meant to be resumed, hence its partial
+            // output and checkpoint are removed.
+            if matches!(error, crate::errors::Error::Cancelled)
+                && let Ok(staging) = crate::staging::staging_directory(path)
+            {
+                let _ = std::fs::remove_dir_all(staging);
+            }
+        })
+    }
+
+    /// Writes out the delta of the knowledge graph since the export in the
+    /// `previous` directory, using the modification columns of the provided
+    /// export options.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to the database connection.
+    /// * `previous` - The directory of the previous export, or of the previous
+    ///   delta.
+    /// * `path` - The path where to write the delta.
+    /// * `options` - The options customizing the export.
+    ///
+    /// # Implementative details
+    ///
+    /// Only the tables with a [modification
+    /// column](ExportOptions::with_modification_column) are queried, for the
+    /// rows modified since the start of the previous export, as recorded in
+    /// its manifest. The delta lists these rows as nodes, together with their
+    /// outgoing foreign key edges, and can be applied to the previous export
+    /// through [`ExportedGraph::apply_delta`](crate::reader::ExportedGraph::apply_delta).
+    /// Categorical columns and edge properties are not part of the delta, and
+    /// as with the full export, foreign keys referencing unique columns other
+    /// than the primary key only yield edges towards existing rows. The rows
+    /// referenced by the delta edges must either be in the previous export or
+    /// in the delta itself for the delta to be applicable.
+    ///
+    /// As for the full export, all of the queries run within a single
+    /// `REPEATABLE READ`, read-only transaction, and the start of the export
+    /// recorded in the manifest is read from the server, as the start of the
+    /// oldest transaction then running on the database. Hence, the rows
+    /// modified by transactions committed after the snapshot of an export
+    /// are selected by the next delta, even if the clocks of the client and
+    /// of the server differ.
+    ///
+    /// As for the full export, the delta is written to the hidden
+    /// `.{name}.partial` sibling directory, which is atomically exchanged
+    /// with the provided path once complete, and removed if the delta fails
+    /// or is [cancelled](ExportOptions::with_cancellation_token). The
+    /// cancellation token is checked before each table.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the previous manifest cannot be
+    /// read, if the provided path is the directory of the previous export, if
+    /// it is the current directory or contains files other than those of an
+    /// export, if the schema changed since the previous export, if the
+    /// previous export has inverse edge classes, if a configured table or
+    /// column does not exist, if the unqualified name of a configured table
+    /// is ambiguous, if the database queries fail, if writing to the files
+    /// fails, or [`Error::Cancelled`](crate::errors::Error::Cancelled) if the
+    /// export is cancelled.
+    #[allow(clippy::too_many_lines)]
+    fn write_kg_delta_csvs(
+        &self,
+        conn: &mut PgConnection,
+        previous: &std::path::Path,
+        path: &std::path::Path,
+        options: &ExportOptions,
+    ) -> Result<Manifest, crate::errors::Error> {
+        let previous_manifest = Manifest::read(previous)?;
+        let schema_fingerprint = self.schema_fingerprint();
+        if previous_manifest.schema_fingerprint() != schema_fingerprint {
+            return Err(crate::errors::Error::InvalidManifest(
+                "the schema changed since the previous export, a full export is required"
+                    .to_owned(),
+            ));
+        }
+        if previous_manifest.options().get("edge_orientation").and_then(serde_json::Value::as_str)
+            == Some(EdgeOrientation::WithInverse.name())
+        {
+            return Err(crate::errors::Error::InvalidManifest(
+                "deltas cannot be applied to exports with inverse edge classes, a full export is \
+                 required"
+                    .to_owned(),
+            ));
+        }
+        let since = i64::try_from(previous_manifest.exported_at()).map_err(|_| {
+            crate::errors::Error::InvalidManifest("the export timestamp is out of range".to_owned())
+        })?;
+
+        if crate::staging::absolute(path)? == crate::staging::absolute(previous)? {
+            return Err(crate::errors::Error::InvalidExport(format!(
+                "the delta cannot be written to the directory of the previous export {}",
+                previous.display()
+            )));
+        }
+
+        // As for the full export, the delta is written to a staging sibling
+        // directory, which is only moved into place once complete.
+        let destination = path;
+        crate::staging::check_destination(destination)?;
+        let staging = crate::staging::staging_directory(destination)?;
+        let path = staging.as_path();
+        if path.exists() {
+            std::fs::remove_dir_all(path)?;
+        }
+        std::fs::create_dir_all(path)?;
+
+        snapshot_transaction(conn, |conn| -> Result<Manifest, crate::errors::Error> {
+            let exported_at = export_timestamp(conn)?;
+
+            let mut nodes_writer = CsvWriter::create(path, "nodes.csv")?;
+            writeln!(nodes_writer, "node,node_class_ids")?;
+            let mut edge_classes_writer = CsvWriter::create(path, "edge_classes.csv")?;
+            writeln!(edge_classes_writer, "edge_class")?;
+            let mut edges_writer = CsvWriter::create(path, "edges.csv")?;
+            writeln!(edges_writer, "src,dst,edge_class")?;
+
+            for (table_name, column_name) in options.modification_columns() {
+                options.check_cancelled()?;
+                let table = find_table(self, table_name)?;
+                if !table.columns(self).any(|col| col.column_name() == column_name) {
+                    return Err(crate::errors::Error::ColumnNotFound(format!(
+                        "{table_name}.{column_name}"
+                    )));
+                }
+                let host_pk_columns =
+                    table.primary_key_columns(self).collect::<Vec<&Self::Column>>();
+                if host_pk_columns.is_empty() {
+                    continue;
+                }
+                let condition = crate::sql::modified_since_condition(column_name);
+
+                let results: Vec<DynamicRow<NamedField<PrimaryKey>>> = match diesel::sql_query(
+                    crate::sql::primary_key_query(table, &host_pk_columns, &condition),
+                )
+                .bind::<diesel::sql_types::BigInt, _>(since)
+                .load(conn)
+                {
+                    Ok(results) => results,
+                    Err(error) => {
+                        return Err(query_error::<Self>(
+                            conn,
+                            &[(table, host_pk_columns.as_slice())],
+                            error,
+                        ));
+                    }
+                };
+                let mut changed_nodes = results
+                    .into_iter()
+                    .map(|row| {
+                        let primary_keys: Vec<PrimaryKey> = row.into();
+                        Node::<Self>::new(table, primary_keys.into())
+                    })
+                    .collect::<Vec<Node<'_, Self>>>();
+                changed_nodes.sort_unstable();
+                let class_ids = std::iter::once(table)
+                    .chain(table.ancestral_extended_tables(self))
+                    .map(|t| {
+                        self.table_id(t).expect("Failed to find tables loaded from the database")
+                    })
+                    .collect::<Vec<usize>>();
+                for node in &changed_nodes {
+                    write_node_row(&mut nodes_writer, node, &class_ids)?;
+                }
+
+                for edge_class in
+                    self.edge_classes().filter(|edge_class| edge_class.host_table() == table)
+                {
+                    writeln!(edge_classes_writer, "{}", CsvField(&edge_class))?;
+                    let referenced_table = edge_class.referenced_table();
+                    let referenced_pk_columns =
+                        referenced_table.primary_key_columns(self).collect::<Vec<&Self::Column>>();
+                    let host_key_columns = host_pk_columns
+                        .iter()
+                        .chain(edge_class.columns())
+                        .copied()
+                        .collect::<Vec<&Self::Column>>();
+                    let candidates = [
+                        (table, host_key_columns.as_slice()),
+                        (referenced_table, referenced_pk_columns.as_slice()),
+                    ];
+                    let mut edges = if edge_class
+                        .referenced_columns()
+                        .iter()
+                        .map(|col| col.column_name())
+                        .eq(referenced_pk_columns.iter().map(|col| col.column_name()))
+                    {
+                        let results: Vec<DynamicRow<NamedField<PrimaryKey>>> =
+                            match diesel::sql_query(crate::sql::foreign_key_values_query(
+                                table,
+                                &host_pk_columns,
+                                edge_class.columns(),
+                                &condition,
+                            ))
+                            .bind::<diesel::sql_types::BigInt, _>(since)
+                            .load(conn)
+                            {
+                                Ok(results) => results,
+                                Err(error) => {
+                                    return Err(query_error::<Self>(conn, &candidates, error));
+                                }
+                            };
+                        results
+                            .into_iter()
+                            .map(|row| {
+                                let mut vals: Vec<PrimaryKey> = row.into();
+                                let fk_vals = vals.split_off(host_pk_columns.len());
+                                (
+                                    Node::<Self>::new(table, vals.into()),
+                                    Node::<Self>::new(referenced_table, fk_vals.into()),
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                    } else {
+                        let results: Vec<DynamicRow<NamedField<PropertyValue>>> =
+                            match diesel::sql_query(crate::sql::referenced_primary_key_query(
+                                table,
+                                &host_pk_columns,
+                                edge_class.columns(),
+                                referenced_table,
+                                edge_class.referenced_columns(),
+                                &referenced_pk_columns,
+                                Some(&condition),
+                            ))
+                            .bind::<diesel::sql_types::BigInt, _>(since)
+                            .load(conn)
+                            {
+                                Ok(results) => results,
+                                Err(error) => {
+                                    return Err(query_error::<Self>(conn, &candidates, error));
+                                }
+                            };
+                        // The references to missing rows are not part of the delta.
+                        split_referenced_rows::<Self>(
+                            results,
+                            (table, &host_pk_columns, edge_class.columns()),
+                            (referenced_table, &referenced_pk_columns),
+                        )?
+                        .0
+                    };
+                    edges.sort_unstable();
+                    for (host_node, referenced_node) in edges {
+                        writeln!(
+                            edges_writer,
+                            "{},{},{}",
+                            CsvField(&host_node),
+                            CsvField(&referenced_node),
+                            CsvField(&edge_class)
+                        )?;
+                    }
+                }
+            }
+
+            let files =
+                vec![nodes_writer.finish()?, edge_classes_writer.finish()?, edges_writer.finish()?];
+            let manifest = Manifest::new(exported_at, schema_fingerprint, files, options.to_json())
+                .with_base_exported_at(previous_manifest.exported_at());
+            manifest.write(path)?;
+            crate::staging::publish(path, destination, options.keeps_backup())?;
+            Ok(manifest)
+        })
+        .inspect_err(|_| {
+            // The delta cannot be resumed, hence its partial files are removed.
+            let _ = std::fs::remove_dir_all(path);
+        })
+    }
+
+    /// Writes out the CSVs representing the schema meta graph at the given
+    /// path, using the same format as the data graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path where to write the CSV files.
+    ///
+    /// # Implementative details
+    ///
+    /// The nodes of the meta graph are the tables, the columns and the foreign
+    /// keys of the database, classified according to [`SchemaNodeClass`].
+    /// Tables with a primary key are also node classes of the data graph,
+    /// and foreign keys referencing such tables are also edge classes of the
+    /// data graph. The edges of the meta graph, classified according to
+    /// [`SchemaEdgeClass`], connect tables to their columns and to the tables
+    /// they extend, and foreign keys to their host and referenced tables and
+    /// columns.
+    ///
+    /// As for the data graph, a `grape.json` file lists the arguments of
+    /// grape's `Graph.from_csv` needed to load the meta graph.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if writing to the files fails.
+    fn write_schema_kg_csvs(
+        &self,
+        path: &std::path::Path,
     ) -> Result<TimeTracker, crate::errors::Error> {
         // If the provided path does not exist, create it.
         if !path.exists() {
             std::fs::create_dir_all(path)?;
         }
 
-        let mut tracker = TimeTracker::new("Write KG CSVs");
-        let task = Task::new("Writing node class CSV");
-        // Write node classes CSV
-        let node_classes_path = path.join("node_classes.csv");
-        let file = std::fs::File::create(node_classes_path)?;
-        let mut write_buffer = std::io::BufWriter::new(file);
-        // Write header
-        writeln!(write_buffer, "node_class")?;
-        for table in self.tables() {
-            let table_schema = table.table_schema();
-            let table_name = table.table_name();
-            if let Some(schema) = table_schema {
-                writeln!(write_buffer, "\"{schema}.{table_name}\"")?;
-            } else {
-                writeln!(write_buffer, "\"{table_name}\"")?;
-            }
+        let mut tracker = TimeTracker::new("Write schema KG CSVs");
+        let task = Task::new("Writing schema node class CSV");
+        let file = std::fs::File::create(path.join("node_classes.csv"))?;
+        let mut node_classes_writer = std::io::BufWriter::new(file);
+        writeln!(node_classes_writer, "node_class")?;
+        for node_class in SchemaNodeClass::ALL {
+            writeln!(node_classes_writer, "{}", CsvField(node_class.name()))?;
         }
-        write_buffer.flush()?;
+        node_classes_writer.flush()?;
         tracker.add_completed_task(task);
 
-        let task = Task::new("Writing node CSV");
-        // Write nodes CSV
-        let nodes_path = path.join("nodes.csv");
-        let file = std::fs::File::create(nodes_path)?;
-        let mut nodes: Vec<Node<'_, Self>> = Vec::with_capacity(self.number_of_nodes(conn)?);
+        let task = Task::new("Writing schema node CSV");
+        let file = std::fs::File::create(path.join("nodes.csv"))?;
         let mut nodes_writer = std::io::BufWriter::new(file);
-        // Write header
         writeln!(nodes_writer, "node,node_class_ids")?;
-        for (table_id, (nodes_result, table)) in self.nodes(conn).zip(self.tables()).enumerate() {
-            let table_nodes = nodes_result?;
-            let ancestor_table_ids = table
-                .ancestral_extended_tables(self)
-                .into_iter()
-                .map(|t| self.table_id(t).expect("Failed to find tables loaded from the database"))
-                .collect::<Vec<usize>>();
-            for node in &table_nodes {
-                write!(nodes_writer, "\"{node}\",{table_id}")?;
-                for ancestor_table_id in &ancestor_table_ids {
-                    write!(nodes_writer, "|{ancestor_table_id}")?;
+        let mut number_of_nodes: usize = 0;
+        let mut edges: Vec<(usize, usize, SchemaEdgeClass)> = Vec::new();
+        let mut add_node =
+            |name: String, node_classes: &[SchemaNodeClass]| -> std::io::Result<usize> {
+                let class_ids = node_classes.iter().map(|class| class.id()).collect::<Vec<usize>>();
+                write_node_row(&mut nodes_writer, name, &class_ids)?;
+                number_of_nodes += 1;
+                Ok(number_of_nodes - 1)
+            };
+
+        // We write the tables first, so that the tables referenced by foreign
+        // keys and extensions are known by the time they are needed.
+        let mut table_ids = Vec::new();
+        for table in self.tables() {
+            let mut node_classes = vec![SchemaNodeClass::Table];
+            if table.has_primary_key(self) {
+                node_classes.push(SchemaNodeClass::NodeClass);
+            }
+            table_ids.push(add_node(node_class_name(table), &node_classes)?);
+        }
+        let table_id = |table: &Self::Table| {
+            table_ids[self.table_id(table).expect("Failed to find tables loaded from the database")]
+        };
+
+        let mut column_ids: HashMap<(usize, &str), usize> = HashMap::new();
+        for table in self.tables() {
+            let host_id = table_id(table);
+            for column in table.columns(self) {
+                let column_id = add_node(
+                    format!("{}.{}", node_class_name(table), Identifier(column.column_name())),
+                    &[SchemaNodeClass::Column],
+                )?;
+                column_ids.insert((host_id, column.column_name()), column_id);
+                edges.push((host_id, column_id, SchemaEdgeClass::HasColumn));
+            }
+            for column in table.primary_key_columns(self) {
+                edges.push((
+                    host_id,
+                    column_ids[&(host_id, column.column_name())],
+                    SchemaEdgeClass::HasPrimaryKeyColumn,
+                ));
+            }
+            for ancestor in table.ancestral_extended_tables(self) {
+                edges.push((host_id, table_id(ancestor), SchemaEdgeClass::Extends));
+            }
+        }
+
+        for table in self.tables() {
+            let host_id = table_id(table);
+            for fk in table.foreign_keys(self) {
+                let referenced_table = fk.referenced_table(self);
+                let referenced_id = table_id(referenced_table);
+                let host_columns = fk.host_columns(self).collect::<Vec<&Self::Column>>();
+                let mut node_classes = vec![SchemaNodeClass::ForeignKey];
+                if table.has_primary_key(self) && referenced_table.has_primary_key(self) {
+                    node_classes.push(SchemaNodeClass::EdgeClass);
+                }
+                let fk_id = add_node(
+                    EdgeClass::<Self>::new(
+                        table,
+                        host_columns.clone(),
+                        referenced_table,
+                        fk.referenced_columns(self).collect(),
+                        fk.foreign_key_name(),
+                    )
+                    .to_string(),
+                    &node_classes,
+                )?;
+                edges.push((host_id, referenced_id, SchemaEdgeClass::References));
+                edges.push((fk_id, host_id, SchemaEdgeClass::HostTable));
+                edges.push((fk_id, referenced_id, SchemaEdgeClass::ReferencedTable));
+                for column in host_columns {
+                    edges.push((
+                        fk_id,
+                        column_ids[&(host_id, column.column_name())],
+                        SchemaEdgeClass::HostColumn,
+                    ));
+                }
+                for column in fk.referenced_columns(self) {
+                    edges.push((
+                        fk_id,
+                        column_ids[&(referenced_id, column.column_name())],
+                        SchemaEdgeClass::ReferencedColumn,
+                    ));
                 }
-                writeln!(nodes_writer)?;
             }
-            nodes.extend(table_nodes);
         }
         nodes_writer.flush()?;
         tracker.add_completed_task(task);
 
-        // Since the tables are sorted and the nodes themselves are sorted within
-        // each table, the nodes are globally sorted.
-        debug_assert!(nodes.windows(2).all(|w| w[0] <= w[1]), "Nodes are not sorted");
-
-        let task = Task::new("Writing edge classes CSV");
-        // Write edge classes CSV
-        let edge_classes_path = path.join("edge_classes.csv");
-        let file = std::fs::File::create(edge_classes_path)?;
+        let task = Task::new("Writing schema edge classes CSV");
+        let file = std::fs::File::create(path.join("edge_classes.csv"))?;
         let mut edge_classes_writer = std::io::BufWriter::new(file);
-        let mut edge_classes: Vec<EdgeClass<'_, Self>> = Vec::new();
-        // Write header
-        writeln!(edge_classes_writer, "edge_class")?;
-        for edge_class in self.edge_classes() {
-            writeln!(edge_classes_writer, "\"{edge_class}\"")?;
-            edge_classes.push(edge_class);
+        writeln!(edge_classes_writer, "edge_class,src_node_class_id,dst_node_class_id")?;
+        for edge_class in SchemaEdgeClass::ALL {
+            writeln!(
+                edge_classes_writer,
+                "{},{},{}",
+                CsvField(edge_class.name()),
+                edge_class.source().id(),
+                edge_class.destination().id()
+            )?;
         }
         edge_classes_writer.flush()?;
         tracker.add_completed_task(task);
 
-        // Since the edge classes are sorted, we can assert that here.
-        debug_assert!(edge_classes.windows(2).all(|w| w[0] <= w[1]), "Edge classes are not sorted");
-
-        let task = Task::new("Writing edges CSV");
-        // Write edges CSV
-        let edges_path = path.join("edges.csv");
-        let file = std::fs::File::create(edges_path)?;
+        let task = Task::new("Writing schema edges CSV");
+        let file = std::fs::File::create(path.join("edges.csv"))?;
         let mut edges_writer = std::io::BufWriter::new(file);
-        // Write header
         writeln!(edges_writer, "src_id,dst_id,edge_class_id")?;
-        for edges_result in self.edges(conn) {
-            let edges = edges_result?;
-            for (host_node, referenced_node, edge_class) in edges {
-                let src_id = nodes
-                    .binary_search(&host_node)
-                    .map_err(|_| crate::errors::Error::NodeNotFound(host_node.to_string()))?;
-                let dst_id = nodes
-                    .binary_search(&referenced_node)
-                    .map_err(|_| crate::errors::Error::NodeNotFound(referenced_node.to_string()))?;
-                let edge_class_id = edge_classes
-                    .binary_search(&edge_class)
-                    .map_err(|_| crate::errors::Error::EdgeClassNotFound(edge_class.to_string()))?;
-                writeln!(edges_writer, "{src_id},{dst_id},{edge_class_id}")?;
-            }
+        for (src_id, dst_id, edge_class) in edges {
+            writeln!(edges_writer, "{src_id},{dst_id},{}", edge_class.id())?;
         }
         edges_writer.flush()?;
         tracker.add_completed_task(task);
 
+        let directory = crate::staging::absolute(path)?;
+        GrapeConfig::new(
+            graph_name(&directory),
+            &directory,
+            number_of_nodes,
+            SchemaNodeClass::ALL.len(),
+            SchemaEdgeClass::ALL.len(),
+        )
+        .write(path)?;
+
         Ok(tracker)
     }
 }
 
+/// Writes out the CSVs representing the knowledge graph at the given path,
+/// using the provided export options, within the current transaction.
+///
+/// # Arguments
+///
+/// * `db` - The database to export.
+/// * `conn` - A mutable reference to the database connection.
+/// * `path` - The path where to write the CSV files.
+/// * `options` - The options customizing the export.
+///
+/// # Errors
+///
+/// Returns the errors documented by
+/// [`write_kg_csvs_with_options`](KGLikeDB::write_kg_csvs_with_options).
+#[allow(clippy::too_many_lines)]
+fn write_kg_csvs_in_transaction<'db, DB: KGLikeDB>(
+    db: &'db DB,
+    conn: &mut PgConnection,
+    path: &std::path::Path,
+    options: &ExportOptions,
+) -> Result<ExportReport<'db, DB>, crate::errors::Error> {
+    // The export is written to a staging sibling directory, which is only
+    // moved into place once every file has been written.
+    let destination = path;
+    crate::staging::check_destination(destination)?;
+    let staging = crate::staging::staging_directory(destination)?;
+    let path = staging.as_path();
+    // Unless resuming, the files left by an interrupted export are discarded,
+    // so that none of them ends up in the published export.
+    if !options.resumes() && path.exists() {
+        std::fs::remove_dir_all(path)?;
+    }
+    if !path.exists() {
+        std::fs::create_dir_all(path)?;
+    }
+
+    // The node class ids of a table are its own id followed by the ids of
+    // the tables it extends.
+    let node_class_ids = |table: &DB::Table| -> Vec<usize> {
+        std::iter::once(table)
+            .chain(table.ancestral_extended_tables(db))
+            .map(|t| db.table_id(t).expect("Failed to find tables loaded from the database"))
+            .collect()
+    };
+
+    // When requested, a checkpoint left by an interrupted export of the same
+    // schema with the same options is resumed.
+    let schema_fingerprint = db.schema_fingerprint();
+    let options_json = options.to_json();
+    let resumed = if options.resumes() {
+        Checkpoint::read(path)
+            .filter(|checkpoint| checkpoint.matches(&schema_fingerprint, &options_json))
+    } else {
+        None
+    };
+    let mut tracker = resumed
+        .as_ref()
+        .and_then(Checkpoint::time_tracker)
+        .unwrap_or_else(|| TimeTracker::new("Write KG CSVs"));
+    let mut checkpoint = match resumed {
+        Some(checkpoint) => checkpoint,
+        None => {
+            Checkpoint::new(
+                schema_fingerprint.clone(),
+                options_json.clone(),
+                export_timestamp(conn)?,
+            )
+        }
+    };
+    let mut files: Vec<ManifestFile> = Vec::new();
+
+    let task = Task::new("Writing node class CSV");
+    options.check_cancelled()?;
+    options.report_progress(ExportPhase::NodeClasses, None, 0, None);
+    // Write node classes CSV
+    let mut write_buffer = CsvWriter::create(path, "node_classes.csv")?;
+    // Write header
+    writeln!(write_buffer, "node_class")?;
+    for table in db.tables() {
+        writeln!(write_buffer, "{}", CsvField(node_class_name(table)))?;
+    }
+    // The categorical columns define synthetic node classes, following the
+    // node classes of the tables.
+    let categorical_columns = options
+        .categorical_columns()
+        .map(|(table_name, column_name)| {
+            let table = find_table(db, table_name)?;
+            if !table.columns(db).any(|col| col.column_name() == column_name) {
+                return Err(crate::errors::Error::ColumnNotFound(format!(
+                    "{table_name}.{column_name}"
+                )));
+            }
+            // The rows of a table without a primary key are not nodes, hence
+            // they cannot be connected to the value nodes.
+            if !table.has_primary_key(db) {
+                return Err(crate::errors::Error::TableWithoutPrimaryKey(node_class_name(table)));
+            }
+            Ok((table, column_name))
+        })
+        .collect::<Result<Vec<(&DB::Table, &str)>, crate::errors::Error>>()?;
+    for (table, column_name) in &categorical_columns {
+        writeln!(
+            write_buffer,
+            "{}",
+            CsvField(format_args!("{}.{}", node_class_name(*table), Identifier(column_name)))
+        )?;
+    }
+    files.push(write_buffer.finish()?);
+    tracker.add_completed_task(task);
+
+    // Write nodes CSV, resuming from the last completed table if any. The
+    // nodes written by the interrupted export are read back, as they are
+    // needed to resolve the identifiers of the edges.
+    // The number of nodes is only used as a capacity hint and progress
+    // total, hence it can be estimated.
+    let number_of_database_nodes = if options.estimates_row_counts() {
+        db.estimated_number_of_nodes(conn)?
+    } else {
+        db.number_of_nodes(conn)?
+    };
+    let mut nodes: Vec<Node<'db, DB>> = Vec::with_capacity(number_of_database_nodes);
+    let mut written_nodes: Vec<NodeName> = Vec::new();
+    let mut nodes_writer = if let Some(length) = checkpoint.file_length("nodes.csv") {
+        let writer = CsvWriter::resume(path, "nodes.csv", length)?;
+        written_nodes = read_node_names(path)?;
+        writer
+    } else {
+        let mut writer = CsvWriter::create(path, "nodes.csv")?;
+        // Write header
+        writeln!(writer, "node,node_class_ids")?;
+        writer
+    };
+    if written_nodes.len() < checkpoint.number_of_database_nodes() {
+        return Err(crate::errors::Error::InvalidExport(format!(
+            "nodes.csv lists {} nodes, while the checkpoint records {}",
+            written_nodes.len(),
+            checkpoint.number_of_database_nodes()
+        )));
+    }
+    for name in written_nodes.drain(..checkpoint.number_of_database_nodes()) {
+        nodes.push(Node::from_name(db, &name).ok_or_else(|| {
+            crate::errors::Error::ResumedNodeNotFound {
+                table: ClassName { schema: name.schema_name(), table: name.table_name() }
+                    .to_string(),
+                key: name.primary_key().clone(),
+            }
+        })?);
+    }
+    options.check_cancelled()?;
+    options.report_progress(ExportPhase::Nodes, None, nodes.len(), Some(number_of_database_nodes));
+    let tables_with_primary_key = db
+        .tables()
+        .filter(|table| table.has_primary_key(db))
+        .skip(checkpoint.completed_node_tables());
+    for (nodes_result, table) in
+        db.nodes_from(conn, checkpoint.completed_node_tables()).zip(tables_with_primary_key)
+    {
+        let task = Task::new(format!("Writing nodes of {}", node_class_name(table)).as_str());
+        let table_nodes = nodes_result?;
+        let class_ids = node_class_ids(table);
+        for (i, node) in table_nodes.iter().enumerate() {
+            write_node_row(&mut nodes_writer, node, &class_ids)?;
+            if (i + 1) % PROGRESS_BATCH_SIZE == 0 {
+                options.check_cancelled()?;
+                options.report_progress(
+                    ExportPhase::Nodes,
+                    Some(&node_class_name(table)),
+                    nodes.len() + i + 1,
+                    Some(number_of_database_nodes),
+                );
+            }
+        }
+        nodes.extend(table_nodes);
+        options.check_cancelled()?;
+        options.report_progress(
+            ExportPhase::Nodes,
+            Some(&node_class_name(table)),
+            nodes.len(),
+            Some(number_of_database_nodes),
+        );
+        tracker.add_completed_task(task);
+        checkpoint.complete_node_table(nodes.len());
+        checkpoint.save(path, &tracker, &mut [&mut nodes_writer])?;
+    }
+
+    // The value nodes of the categorical columns follow the nodes read from
+    // the database, sorted by value within each column. Their values are
+    // read again when resuming, but only written once.
+    let task = Task::new("Writing value nodes");
+    options.check_cancelled()?;
+    options.report_progress(ExportPhase::ValueNodes, None, 0, None);
+    let number_of_tables = db.tables().count();
+    let mut number_of_written_nodes = nodes.len();
+    let mut categorical_values: Vec<(BTreeMap<PrimaryKey, String>, HashMap<String, usize>)> =
+        Vec::with_capacity(categorical_columns.len());
+    for (i, (table, column_name)) in categorical_columns.iter().enumerate() {
+        let values = text_values(db, conn, table, column_name)?;
+        let distinct_values: BTreeSet<&String> = values.values().collect();
+        let mut value_ids = HashMap::with_capacity(distinct_values.len());
+        for value in distinct_values {
+            if !checkpoint.nodes_completed() {
+                write_node_row(
+                    &mut nodes_writer,
+                    NodeName::value(
+                        table.table_schema().map(str::to_owned),
+                        table.table_name().to_owned(),
+                        (*column_name).to_owned(),
+                        value.clone(),
+                    ),
+                    &[number_of_tables + i],
+                )?;
+            }
+            value_ids.insert(value.clone(), number_of_written_nodes);
+            number_of_written_nodes += 1;
+        }
+        options.check_cancelled()?;
+        options.report_progress(
+            ExportPhase::ValueNodes,
+            Some(&format_args!("{}.{}", node_class_name(*table), Identifier(column_name))),
+            number_of_written_nodes - nodes.len(),
+            None,
+        );
+        categorical_values.push((values, value_ids));
+    }
+    if !checkpoint.nodes_completed() {
+        tracker.add_completed_task(task);
+        checkpoint.complete_nodes();
+        checkpoint.save(path, &tracker, &mut [&mut nodes_writer])?;
+    }
+
+    // Since the tables are sorted and the nodes themselves are sorted within
+    // each table, the nodes are globally sorted.
+    debug_assert!(nodes.windows(2).all(|w| w[0] <= w[1]), "Nodes are not sorted");
+
+    let task = Task::new("Writing edge classes CSV");
+    options.check_cancelled()?;
+    options.report_progress(ExportPhase::EdgeClasses, None, 0, None);
+    // Write edge classes CSV
+    let mut edge_classes_writer = CsvWriter::create(path, "edge_classes.csv")?;
+    let mut edge_classes: Vec<EdgeClass<'db, DB>> = Vec::new();
+    // The rows of the edge classes, as triples of name, source node class id
+    // and destination node class id.
+    let mut edge_class_rows: Vec<(String, usize, usize)> = Vec::new();
+    for edge_class in db.edge_classes() {
+        let src_node_class_id = db
+            .table_id(edge_class.host_table())
+            .expect("Failed to find tables loaded from the database");
+        let dst_node_class_id = db
+            .table_id(edge_class.referenced_table())
+            .expect("Failed to find tables loaded from the database");
+        edge_class_rows.push((edge_class.to_string(), src_node_class_id, dst_node_class_id));
+        edge_classes.push(edge_class);
+    }
+    // The categorical columns define edge classes following the ones of the
+    // foreign keys.
+    for (i, (table, column_name)) in categorical_columns.iter().enumerate() {
+        let src_node_class_id =
+            db.table_id(table).expect("Failed to find tables loaded from the database");
+        edge_class_rows.push((
+            format!("{}({})", node_class_name(*table), Identifier(column_name)),
+            src_node_class_id,
+            number_of_tables + i,
+        ));
+    }
+    // The inverse edge classes follow all of the other edge classes, so that
+    // the identifier of an inverse edge class is the one of the original
+    // edge class offset by their number.
+    let inverse_offset = (options.edge_orientation() == EdgeOrientation::WithInverse)
+        .then_some(edge_class_rows.len());
+    if inverse_offset.is_some() {
+        let inverse_rows = edge_class_rows
+            .iter()
+            .map(|(name, src_node_class_id, dst_node_class_id)| {
+                (format!("{name}^-1"), *dst_node_class_id, *src_node_class_id)
+            })
+            .collect::<Vec<(String, usize, usize)>>();
+        edge_class_rows.extend(inverse_rows);
+    }
+    // Write header
+    writeln!(edge_classes_writer, "edge_class,src_node_class_id,dst_node_class_id")?;
+    for (name, src_node_class_id, dst_node_class_id) in &edge_class_rows {
+        writeln!(
+            edge_classes_writer,
+            "{},{src_node_class_id},{dst_node_class_id}",
+            CsvField(name)
+        )?;
+    }
+    files.push(edge_classes_writer.finish()?);
+    tracker.add_completed_task(task);
+
+    // Since the edge classes are sorted, we can assert that here.
+    debug_assert!(edge_classes.windows(2).all(|w| w[0] <= w[1]), "Edge classes are not sorted");
+
+    // We load the edge properties ahead of the edges, as the connection is
+    // borrowed by the edges iterator.
+    let mut edge_properties: HashMap<
+        &DB::Table,
+        (&[String], HashMap<PrimaryKey, Vec<PropertyValue>>),
+    > = HashMap::new();
+    for table_name in options.edge_property_tables() {
+        let table = find_table(db, table_name)?;
+        let column_names =
+            options.edge_properties(table_name).expect("Table name was provided by options");
+        edge_properties
+            .insert(table, (column_names, db.row_properties(conn, table, column_names)?));
+    }
+
+    // Write edges CSV, resuming from the last completed edge class if any.
+    let resume_or_create = |file_name: &str, header: &str| -> std::io::Result<CsvWriter> {
+        if let Some(length) = checkpoint.file_length(file_name) {
+            CsvWriter::resume(path, file_name, length)
+        } else {
+            let mut writer = CsvWriter::create(path, file_name)?;
+            writeln!(writer, "{header}")?;
+            Ok(writer)
+        }
+    };
+    let mut edges_writer = resume_or_create("edges.csv", "src_id,dst_id,edge_class_id")?;
+    let mut edge_properties_writer = if options.has_edge_properties() {
+        Some(resume_or_create("edge_properties.csv", "edge_id,property,data_type,value")?)
+    } else {
+        None
+    };
+    // Placeholder nodes are appended after the nodes read from the database,
+    // so that the identifiers of the latter are left unchanged. Those written
+    // by the interrupted export are the remaining nodes read back.
+    let mut placeholders: HashMap<Node<'db, DB>, usize> =
+        HashMap::with_capacity(written_nodes.len().saturating_sub(number_of_written_nodes));
+    for name in written_nodes.iter().skip(number_of_written_nodes - nodes.len()) {
+        let placeholder = Node::from_name(db, name)
+            .ok_or_else(|| crate::errors::Error::NodeNotFound(name.to_string()))?;
+        placeholders.insert(placeholder, number_of_written_nodes);
+        number_of_written_nodes += 1;
+    }
+    let mut dangling_references: BTreeMap<EdgeClass<'db, DB>, usize> = checkpoint
+        .dangling_references()
+        .iter()
+        .map(|(edge_class_id, count)| (edge_classes[*edge_class_id].clone(), *count))
+        .collect();
+    let mut edge_id: usize = checkpoint.number_of_edges();
+    options.check_cancelled()?;
+    options.report_progress(ExportPhase::Edges, None, edge_id, None);
+    for edges_result in db.edges_from(conn, checkpoint.completed_edge_classes()) {
+        let (current_edge_class, edges, unresolved_references) = edges_result?;
+        let task = Task::new(format!("Writing edges of {current_edge_class}").as_str());
+        for (i, (host_node, referenced_node, edge_class)) in edges.into_iter().enumerate() {
+            if i > 0 && i % PROGRESS_BATCH_SIZE == 0 {
+                options.check_cancelled()?;
+                options.report_progress(
+                    ExportPhase::Edges,
+                    Some(&current_edge_class),
+                    edge_id,
+                    None,
+                );
+            }
+            let src_id = nodes
+                .binary_search(&host_node)
+                .map_err(|_| crate::errors::Error::NodeNotFound(host_node.to_string()))?;
+            let edge_class_id = edge_classes
+                .binary_search(&edge_class)
+                .map_err(|_| crate::errors::Error::EdgeClassNotFound(edge_class.to_string()))?;
+            let dst_id = if let Ok(dst_id) = nodes.binary_search(&referenced_node) {
+                dst_id
+            } else {
+                match options.dangling_reference_policy() {
+                    DanglingReferencePolicy::Fail => {
+                        return Err(crate::errors::Error::DanglingReference {
+                            edge_class: edge_class.to_string(),
+                            row: host_node.to_string(),
+                            referenced: referenced_node.to_string(),
+                        });
+                    }
+                    DanglingReferencePolicy::Skip => {
+                        *dangling_references.entry(edge_class).or_default() += 1;
+                        continue;
+                    }
+                    DanglingReferencePolicy::Placeholder => {
+                        *dangling_references.entry(edge_class).or_default() += 1;
+                        if let Some(dst_id) = placeholders.get(&referenced_node) {
+                            *dst_id
+                        } else {
+                            let dst_id = number_of_written_nodes;
+                            number_of_written_nodes += 1;
+                            write_node_row(
+                                &mut nodes_writer,
+                                &referenced_node,
+                                &node_class_ids(referenced_node.table()),
+                            )?;
+                            placeholders.insert(referenced_node, dst_id);
+                            dst_id
+                        }
+                    }
+                }
+            };
+            writeln!(edges_writer, "{src_id},{dst_id},{edge_class_id}")?;
+            if let (Some(writer), Some((column_names, rows))) =
+                (edge_properties_writer.as_mut(), edge_properties.get(host_node.table()))
+                && let Some(values) = rows.get(host_node.primary_key())
+            {
+                for (column_name, value) in column_names.iter().zip(values) {
+                    if value.is_null() {
+                        continue;
+                    }
+                    writeln!(
+                        writer,
+                        "{edge_id},{},{},{}",
+                        CsvField(column_name),
+                        CsvField(value.data_type()),
+                        CsvField(value)
+                    )?;
+                }
+            }
+            edge_id += 1;
+            // The reverse edge immediately follows the original one.
+            if let Some(offset) = inverse_offset {
+                writeln!(edges_writer, "{dst_id},{src_id},{}", edge_class_id + offset)?;
+                edge_id += 1;
+            }
+        }
+        // The references through unique columns to missing rows are handled
+        // by the same policy, but as the primary key of the missing row is
+        // unknown, no placeholder node can be created for them.
+        if let Some((host_node, values)) = unresolved_references.first()
+            && options.dangling_reference_policy() == DanglingReferencePolicy::Fail
+        {
+            return Err(crate::errors::Error::DanglingReference {
+                edge_class: current_edge_class.to_string(),
+                row: host_node.to_string(),
+                referenced: format!(
+                    "{}({}) = ({values})",
+                    node_class_name(current_edge_class.referenced_table()),
+                    current_edge_class
+                        .referenced_columns()
+                        .iter()
+                        .map(|col| Identifier(col.column_name()).to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            });
+        }
+        if !unresolved_references.is_empty() {
+            *dangling_references.entry(current_edge_class.clone()).or_default() +=
+                unresolved_references.len();
+        }
+        options.check_cancelled()?;
+        options.report_progress(ExportPhase::Edges, Some(&current_edge_class), edge_id, None);
+        tracker.add_completed_task(task);
+        checkpoint.complete_edge_class(
+            edge_id,
+            dangling_references
+                .iter()
+                .map(|(edge_class, count)| {
+                    let edge_class_id = edge_classes
+                        .binary_search(edge_class)
+                        .expect("Dangling references are only counted for known edge classes");
+                    (edge_class_id, *count)
+                })
+                .collect(),
+        );
+        let mut writers = vec![&mut nodes_writer, &mut edges_writer];
+        writers.extend(edge_properties_writer.as_mut());
+        checkpoint.save(path, &tracker, &mut writers)?;
+    }
+
+    let task = Task::new("Writing value edges");
+    let number_of_value_edges = categorical_values.iter().map(|(values, _)| values.len()).sum();
+    let mut number_of_written_value_edges = 0;
+    options.check_cancelled()?;
+    options.report_progress(ExportPhase::ValueEdges, None, 0, Some(number_of_value_edges));
+    for (i, ((table, column_name), (values, value_ids))) in
+        categorical_columns.iter().zip(&categorical_values).enumerate()
+    {
+        let edge_class_id = edge_classes.len() + i;
+        for (primary_key, value) in values {
+            let host_node = Node::new(*table, primary_key.clone());
+            let src_id = nodes
+                .binary_search(&host_node)
+                .map_err(|_| crate::errors::Error::NodeNotFound(host_node.to_string()))?;
+            let dst_id = value_ids[value];
+            writeln!(edges_writer, "{src_id},{dst_id},{edge_class_id}")?;
+            if let Some(offset) = inverse_offset {
+                writeln!(edges_writer, "{dst_id},{src_id},{}", edge_class_id + offset)?;
+            }
+        }
+        number_of_written_value_edges += values.len();
+        options.check_cancelled()?;
+        options.report_progress(
+            ExportPhase::ValueEdges,
+            Some(&format_args!("{}({})", node_class_name(*table), Identifier(column_name))),
+            number_of_written_value_edges,
+            Some(number_of_value_edges),
+        );
+    }
+    files.push(edges_writer.finish()?);
+    if let Some(writer) = edge_properties_writer {
+        files.push(writer.finish()?);
+    }
+    // The nodes file is finished last, as placeholder nodes may have been
+    // appended to it while writing the edges.
+    files.insert(1, nodes_writer.finish()?);
+    tracker.add_completed_task(task);
+
+    let statistics = if options.computes_statistics() {
+        let task = Task::new("Computing statistics");
+        let node_class_names = db
+            .tables()
+            .map(node_class_name)
+            .chain(categorical_columns.iter().map(|(table, column_name)| {
+                format!("{}.{}", node_class_name(*table), Identifier(column_name))
+            }))
+            .collect();
+        let table_id = |table: &DB::Table| {
+            db.table_id(table).expect("Failed to find tables loaded from the database")
+        };
+        let mut first_node_class_ids = vec![0; number_of_written_nodes];
+        for (node_id, node) in nodes.iter().enumerate() {
+            first_node_class_ids[node_id] = table_id(node.table());
+        }
+        for (i, (_, value_ids)) in categorical_values.iter().enumerate() {
+            for node_id in value_ids.values() {
+                first_node_class_ids[*node_id] = number_of_tables + i;
+            }
+        }
+        for (node, node_id) in &placeholders {
+            first_node_class_ids[*node_id] = table_id(node.table());
+        }
+        let edge_class_names = edge_class_rows.iter().map(|(name, _, _)| name.clone()).collect();
+        // The rows skipped because of null values are counted for the edge
+        // classes of the foreign keys, followed by the ones of the
+        // categorical columns.
+        let skippable_columns = edge_classes
+            .iter()
+            .map(|edge_class| {
+                (
+                    edge_class.host_table(),
+                    edge_class.columns().iter().map(|col| col.column_name()).collect(),
+                )
+            })
+            .chain(
+                categorical_columns.iter().map(|(table, column_name)| (*table, vec![*column_name])),
+            )
+            .collect::<Vec<(&DB::Table, Vec<&str>)>>();
+        let mut null_references = Vec::with_capacity(skippable_columns.len());
+        for (table, column_names) in skippable_columns {
+            options.check_cancelled()?;
+            let count =
+                load_bigint(conn, &crate::sql::null_references_count_query(table, &column_names))
+                    .map_err(|source| {
+                    crate::errors::Error::Query { table: node_class_name(table), source }
+                })?;
+            null_references.push(count.and_then(|count| usize::try_from(count).ok()).unwrap_or(0));
+        }
+        // The inverse edge classes skip the same rows as the original ones.
+        if inverse_offset.is_some() {
+            null_references.extend_from_within(..);
+        }
+        let statistics = ExportStatistics::compute(
+            path,
+            node_class_names,
+            &first_node_class_ids,
+            edge_class_names,
+            &null_references,
+        )?;
+        statistics.write(path)?;
+        tracker.add_completed_task(task);
+        Some(statistics)
+    } else {
+        None
+    };
+
+    let task = Task::new("Writing manifest and publishing the export");
+    options.check_cancelled()?;
+    options.report_progress(ExportPhase::Publishing, None, 0, None);
+    let manifest = Manifest::new(checkpoint.exported_at(), schema_fingerprint, files, options_json);
+    manifest.write(path)?;
+    manifest.grape_config(destination)?.write(path)?;
+    Checkpoint::remove(path)?;
+    crate::staging::publish(path, destination, options.keeps_backup())?;
+    tracker.add_completed_task(task);
+
+    Ok(ExportReport::new(tracker, dangling_references, manifest, statistics))
+}
+
+/// Returns the edge classes hosted by the provided table, sorted in the
+/// order of [`KGLikeDB::edge_classes`].
+///
+/// # Arguments
+///
+/// * `database` - The database containing the table.
+/// * `table` - The host table.
+///
+/// # Implementative details
+///
+/// Only the tables with a primary key host edge classes, and only the
+/// foreign keys pointing to tables with a primary key define them, as the
+/// rows of the other tables are not nodes.
+pub(crate) fn table_edge_classes<'db, DB: DatabaseLike>(
+    database: &'db DB,
+    table: &'db DB::Table,
+) -> Vec<EdgeClass<'db, DB>> {
+    if !table.has_primary_key(database) {
+        return Vec::new();
+    }
+    let mut edge_classes = table
+        .foreign_keys(database)
+        .filter(|fk| fk.referenced_table(database).has_primary_key(database))
+        .map(|fk| {
+            EdgeClass::new(
+                table,
+                fk.host_columns(database).collect(),
+                fk.referenced_table(database),
+                fk.referenced_columns(database).collect(),
+                fk.foreign_key_name(),
+            )
+        })
+        .collect::<Vec<EdgeClass<'db, DB>>>();
+    edge_classes.sort_unstable();
+    edge_classes
+}
+
+/// Returns the number of edge classes hosted by the provided table, without
+/// building them.
+///
+/// # Arguments
+///
+/// * `database` - The database containing the table.
+/// * `table` - The host table.
+pub(crate) fn number_of_table_edge_classes<DB: DatabaseLike>(
+    database: &DB,
+    table: &DB::Table,
+) -> usize {
+    if !table.has_primary_key(database) {
+        return 0;
+    }
+    table
+        .foreign_keys(database)
+        .filter(|fk| fk.referenced_table(database).has_primary_key(database))
+        .count()
+}
+
+/// Reads back the names of the nodes written to the `nodes.csv` file of the
+/// provided directory.
+///
+/// # Arguments
+///
+/// * `directory` - The export directory.
+fn read_node_names(directory: &std::path::Path) -> Result<Vec<NodeName>, crate::errors::Error> {
+    let content = std::fs::read_to_string(directory.join("nodes.csv"))?;
+    parse_records(&content)
+        .map_err(crate::errors::Error::InvalidExport)?
+        .into_iter()
+        .skip(1)
+        .map(|record| record[0].parse::<NodeName>())
+        .collect()
+}
+
+/// Returns the UNIX timestamp, in seconds, recorded as the start of an export
+/// and used as the cutoff of the next delta.
+///
+/// # Arguments
+///
+/// * `conn` - A mutable reference to the database connection, within the
+///   transaction of the export.
+///
+/// # Implementative details
+///
+/// The timestamp is read from the server rather than from the local clock,
+/// so that it is comparable with the modification timestamps written by the
+/// server. It is the start of the oldest transaction running on the
+/// database, and at most the start of the current transaction, so that the
+/// rows modified by transactions which are not yet committed when the
+/// snapshot is taken, and are therefore not exported, are selected by the
+/// next delta. The transactions of other users are only visible to the
+/// superusers and the members of `pg_read_all_stats`.
+fn export_timestamp(conn: &mut PgConnection) -> Result<u64, crate::errors::Error> {
+    let timestamp = load_bigint(conn, crate::sql::EXPORT_TIMESTAMP_QUERY)?;
+    Ok(timestamp.and_then(|timestamp| u64::try_from(timestamp).ok()).unwrap_or(0))
+}
+
+/// Returns whether the provided connection is within a transaction.
+///
+/// # Arguments
+///
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// Returns an [`Error::Diesel`](crate::errors::Error::Diesel) if the status
+/// of the transaction manager is broken by a previous failure.
+fn is_within_transaction(conn: &mut PgConnection) -> Result<bool, crate::errors::Error> {
+    Ok(<diesel::connection::AnsiTransactionManager as diesel::connection::TransactionManager<
+        PgConnection,
+    >>::transaction_manager_status_mut(conn)
+    .transaction_depth()?
+    .is_some())
+}
+
+/// Runs the provided closure within a `REPEATABLE READ`, read-only
+/// transaction, or within a savepoint of the current transaction when the
+/// connection is already within one.
+///
+/// # Arguments
+///
+/// * `conn` - A mutable reference to the database connection.
+/// * `run` - The closure running the queries.
+///
+/// # Implementative details
+///
+/// The isolation level of a transaction opened by the caller cannot be
+/// changed, hence the queries only read from a single snapshot if the
+/// caller opened a `REPEATABLE READ` transaction. The savepoint lets the
+/// transaction of the caller be used again after a failed query.
+///
+/// # Errors
+///
+/// Returns the error of the closure, or an
+/// [`Error::Diesel`](crate::errors::Error::Diesel) if the transaction cannot
+/// be opened or committed.
+fn snapshot_transaction<T>(
+    conn: &mut PgConnection,
+    run: impl FnOnce(&mut PgConnection) -> Result<T, crate::errors::Error>,
+) -> Result<T, crate::errors::Error> {
+    if is_within_transaction(conn)? {
+        conn.transaction(run)
+    } else {
+        conn.build_transaction().repeatable_read().read_only().run(run)
+    }
+}
+
+/// Converts the provided values of the provided columns of a row into primary
+/// keys.
+///
+/// # Arguments
+///
+/// * `table` - The table of the row.
+/// * `columns` - The columns the values were read from.
+/// * `values` - The values of the columns.
+///
+/// # Errors
+///
+/// Returns an
+/// [`Error::UnsupportedKeyType`](crate::errors::Error::UnsupportedKeyType)
+/// naming the first column whose value cannot be a primary key.
+fn primary_keys<DB: KGLikeDB>(
+    table: &DB::Table,
+    columns: &[&DB::Column],
+    values: Vec<PropertyValue>,
+) -> Result<Vec<PrimaryKey>, crate::errors::Error> {
+    values
+        .into_iter()
+        .zip(columns)
+        .map(|(value, column)| {
+            PrimaryKey::try_from(value).map_err(|value| {
+                crate::errors::Error::UnsupportedKeyType {
+                    table: node_class_name(table),
+                    column: column.column_name().to_owned(),
+                    oid: value.oid().map_or(0, std::num::NonZeroU32::get),
+                }
+            })
+        })
+        .collect()
+}
+
+/// Splits the rows selected by
+/// [`referenced_primary_key_query`](crate::sql::referenced_primary_key_query)
+/// into the edges towards the referenced rows and the dangling references,
+/// whose referenced primary key is null.
+///
+/// # Arguments
+///
+/// * `rows` - The selected rows.
+/// * `host` - The host table, with its primary key and foreign key columns.
+/// * `referenced` - The referenced table, with its primary key columns.
+///
+/// # Errors
+///
+/// Returns an
+/// [`Error::UnsupportedKeyType`](crate::errors::Error::UnsupportedKeyType)
+/// if a key value cannot be a primary key.
+#[allow(clippy::type_complexity)]
+fn split_referenced_rows<'db, DB: KGLikeDB>(
+    rows: Vec<DynamicRow<NamedField<PropertyValue>>>,
+    (host_table, host_pk_columns, host_fk_columns): (
+        &'db DB::Table,
+        &[&DB::Column],
+        &[&DB::Column],
+    ),
+    (referenced_table, referenced_pk_columns): (&'db DB::Table, &[&DB::Column]),
+) -> Result<
+    (Vec<(Node<'db, DB>, Node<'db, DB>)>, Vec<(Node<'db, DB>, PrimaryKey)>),
+    crate::errors::Error,
+> {
+    let mut edges = Vec::with_capacity(rows.len());
+    let mut dangling_references = Vec::new();
+    for row in rows {
+        let mut values: Vec<PropertyValue> = row.into();
+        let referenced_values = values.split_off(host_pk_columns.len() + host_fk_columns.len());
+        let fk_values = values.split_off(host_pk_columns.len());
+        let host_node =
+            Node::new(host_table, primary_keys::<DB>(host_table, host_pk_columns, values)?.into());
+        if referenced_values.iter().all(PropertyValue::is_null) {
+            dangling_references.push((
+                host_node,
+                primary_keys::<DB>(host_table, host_fk_columns, fk_values)?.into(),
+            ));
+        } else {
+            edges.push((
+                host_node,
+                Node::new(
+                    referenced_table,
+                    primary_keys::<DB>(referenced_table, referenced_pk_columns, referenced_values)?
+                        .into(),
+                ),
+            ));
+        }
+    }
+    Ok((edges, dangling_references))
+}
+
+/// Returns the non-null values of the provided column of the provided table,
+/// as text, indexed by the primary key of their row.
+///
+/// # Arguments
+///
+/// * `db` - The database containing the table.
+/// * `conn` - A mutable reference to the database connection.
+/// * `table` - The table containing the column.
+/// * `column_name` - The name of the column.
+///
+/// # Errors
+///
+/// Returns an [`Error::Query`](crate::errors::Error::Query) or an
+/// [`Error::UnsupportedKeyType`](crate::errors::Error::UnsupportedKeyType)
+/// if the query fails.
+fn text_values<DB: KGLikeDB>(
+    db: &DB,
+    conn: &mut PgConnection,
+    table: &DB::Table,
+    column_name: &str,
+) -> Result<BTreeMap<PrimaryKey, String>, crate::errors::Error> {
+    let primary_key_columns = table.primary_key_columns(db).collect::<Vec<&DB::Column>>();
+    let results: Vec<DynamicRow<NamedField<PropertyValue>>> = match diesel::sql_query(
+        crate::sql::text_values_query(table, &primary_key_columns, column_name),
+    )
+    .load(conn)
+    {
+        Ok(results) => results,
+        Err(error) => {
+            return Err(query_error::<DB>(conn, &[(table, primary_key_columns.as_slice())], error));
+        }
+    };
+
+    let mut values = BTreeMap::new();
+    for row in results {
+        let mut vals: Vec<PropertyValue> = row.into();
+        let Some(PropertyValue::String(value)) = vals.pop() else {
+            continue;
+        };
+        let primary_key = primary_keys::<DB>(table, &primary_key_columns, vals)?;
+        values.insert(primary_key.into(), value);
+    }
+    Ok(values)
+}
+
+/// Loads the single 64-bit integer selected by the provided query, if any.
+///
+/// # Arguments
+///
+/// * `conn` - A mutable reference to the database connection.
+/// * `query` - The query selecting a single row with a single `bigint` column.
+fn load_bigint(conn: &mut PgConnection, query: &str) -> Result<Option<i64>, diesel::result::Error> {
+    let rows: Vec<DynamicRow<NamedField<PrimaryKey>>> = diesel::sql_query(query).load(conn)?;
+    Ok(rows.into_iter().next().and_then(|row| {
+        let mut values: Vec<PrimaryKey> = row.into();
+        match values.pop()? {
+            PrimaryKey::I64(value) => Some(value),
+            _ => None,
+        }
+    }))
+}
+
+/// Returns the error describing the failure of a query on the provided
+/// tables, identifying the key column whose type is not supported, if any.
+///
+/// # Arguments
+///
+/// * `conn` - A mutable reference to the database connection.
+/// * `candidates` - The queried tables, the first being the main one, each with
+///   the key columns whose values are read as primary keys.
+/// * `error` - The error returned by the query.
+///
+/// # Implementative details
+///
+/// When a value of an unsupported type is read, only the OID of its type is
+/// known, hence the catalog is queried for the candidate column of that
+/// type. If none is found, as it happens when the unsupported column is not
+/// a key column, the error is reported as a failure of the query on the main
+/// table.
+fn query_error<DB: KGLikeDB>(
+    conn: &mut PgConnection,
+    candidates: &[(&DB::Table, &[&DB::Column])],
+    error: diesel::result::Error,
+) -> crate::errors::Error {
+    if let diesel::result::Error::DeserializationError(source) = &error
+        && let Some(UnsupportedOid(oid)) = source.downcast_ref::<UnsupportedOid>()
+    {
+        for (table, columns) in candidates {
+            let column = diesel::sql_query(crate::sql::columns_of_type_query(*table, columns))
+                .bind::<diesel::sql_types::BigInt, _>(i64::from(oid.get()))
+                .load::<DynamicRow<NamedField<PrimaryKey>>>(conn)
+                .ok()
+                .and_then(|rows| {
+                    let mut values: Vec<PrimaryKey> = rows.into_iter().next()?.into();
+                    match values.pop()? {
+                        PrimaryKey::String(column) => Some(column),
+                        _ => None,
+                    }
+                });
+            if let Some(column) = column {
+                return crate::errors::Error::UnsupportedKeyType {
+                    table: node_class_name(*table),
+                    column,
+                    oid: oid.get(),
+                };
+            }
+        }
+    }
+    crate::errors::Error::Query {
+        table: candidates.first().map(|(table, _)| node_class_name(*table)).unwrap_or_default(),
+        source: error,
+    }
+}
+
+/// Returns the name of the node class associated to the provided table.
+///
+/// # Arguments
+///
+/// * `table` - The table whose node class name should be returned.
+fn node_class_name<T: TableLike>(table: &T) -> String {
+    ClassName { schema: table.table_schema(), table: table.table_name() }.to_string()
+}
+
+/// Returns the table of the provided database named in the export options,
+/// either by its name alone or qualified with its schema, such as
+/// `public.users`.
+///
+/// # Arguments
+///
+/// * `db` - The database containing the table.
+/// * `table_name` - The name of the table, optionally schema-qualified.
+///
+/// # Errors
+///
+/// Returns an [`Error::TableNotFound`](crate::errors::Error::TableNotFound)
+/// if no table matches the name, or an
+/// [`Error::AmbiguousTable`](crate::errors::Error::AmbiguousTable) if the
+/// unqualified name matches tables of several schemas.
+fn find_table<'db, DB: KGLikeDB>(
+    db: &'db DB,
+    table_name: &str,
+) -> Result<&'db DB::Table, crate::errors::Error> {
+    let mut tables = db.tables().filter(|table| {
+        table.table_name() == table_name
+            || table.table_schema().is_some_and(|schema| {
+                table_name
+                    .strip_prefix(schema)
+                    .and_then(|name| name.strip_prefix('.'))
+                    .is_some_and(|name| name == table.table_name())
+            })
+    });
+    let table =
+        tables.next().ok_or_else(|| crate::errors::Error::TableNotFound(table_name.to_owned()))?;
+    if tables.next().is_some() {
+        return Err(crate::errors::Error::AmbiguousTable(table_name.to_owned()));
+    }
+    Ok(table)
+}
+
 impl<KG> KGLikeDB for KG where KG: DatabaseLike {}